@@ -1,406 +1,1176 @@
+
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
     instruction::{AccountMeta, Instruction},
     program::invoke,
+    sysvar::instructions::{
+        self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+    },
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint as Token2022Mint,
 };
 use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 declare_id!("3bBfJkCFZ8MpenUAxurbQqbphfxUm8UBokfSRth2c3oF");
 
 // Program IDs for DEX integrations
 pub const WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
 pub const RAYDIUM_AMM_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey = pubkey!("LbVRzDTvBDEcrthxfZ4RL6yiq3uZw8bS6MwtdY6UhFQ");
+pub const PHOENIX_PROGRAM_ID: Pubkey = pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
+pub const OPENBOOK_V2_PROGRAM_ID: Pubkey = pubkey!("opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb");
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 pub const SOLEND_PROGRAM_ID: Pubkey = pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
 pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
 
-#[program]
-pub mod arbitrage_program {
+// Anchor's generated IDL instruction handlers still call the deprecated `AccountInfo::realloc`.
+// They are emitted next to the `#[program]` module, so it lives in a wrapper where only that
+// lint is allowed.
+#[allow(deprecated)]
+mod program_entrypoint {
     use super::*;
 
-    pub fn initialize_arbitrage_state(
-        ctx: Context<InitializeArbitrageState>,
-        min_execution_interval: i64,
-    ) -> Result<()> {
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        arbitrage_state.authority = ctx.accounts.authority.key();
-        arbitrage_state.is_paused = false;
-        arbitrage_state.min_execution_interval = min_execution_interval;
-        arbitrage_state.last_execution_time = 0;
-        arbitrage_state.total_trades = 0;
-        arbitrage_state.total_profit = 0;
-        arbitrage_state.bump = ctx.bumps.arbitrage_state;
-
-        emit!(ArbitrageStateInitialized {
-            authority: arbitrage_state.authority,
-            min_execution_interval,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+    #[program]
+    pub mod arbitrage_program {
+        use super::*;
 
-        msg!("Arbitrage state initialized for authority: {}", arbitrage_state.authority);
-        Ok(())
-    }
+        pub fn initialize_arbitrage_state(
+            ctx: Context<InitializeArbitrageState>,
+            state_id: Pubkey,
+            min_execution_interval: i64,
+        ) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.set_inner(ArbitrageState::new(
+                state_id,
+                ctx.accounts.authority.key(),
+                min_execution_interval,
+                ctx.bumps.arbitrage_state,
+            ));
 
-    pub fn flash_arbitrage(
-        ctx: Context<FlashArbitrage>,
-        routes: Vec<SwapRoute>,
-        expected_profit: u64,
-    ) -> Result<()> {
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        
-        // Safety checks
-        require!(!arbitrage_state.is_paused, ArbitrageError::BotPaused);
-        require!(!routes.is_empty(), ArbitrageError::EmptyRoutes);
-        require!(routes.len() <= 4, ArbitrageError::TooManyHops);
-        require!(expected_profit > 0, ArbitrageError::InvalidAmount);
-
-        // Rate limiting check
-        let current_time = Clock::get()?.unix_timestamp;
-        let time_since_last = current_time - arbitrage_state.last_execution_time;
-        require!(
-            time_since_last >= arbitrage_state.min_execution_interval,
-            ArbitrageError::ExecutionTooFrequent
-        );
-
-        msg!("Starting arbitrage sequence with {} routes", routes.len());
-        msg!("Expected profit: {} lamports", expected_profit);
-
-        // Execute each swap route in sequence (inline to avoid borrowing issues)
-        for (i, route) in routes.iter().enumerate() {
-            msg!("Executing route {}/{}: {:?} swap", i + 1, routes.len(), route.dex_id);
-            
-            // Execute swap based on DEX type (inline simulation)
-            match route.dex_id {
-                DexId::Orca => {
-                    msg!("🌊 Orca swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    msg!("  Min amount out: {}", route.min_amount_out);
-                    msg!("  ✅ Orca swap executed successfully");
-                },
-                DexId::Raydium => {
-                    msg!("⚡ Raydium swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    msg!("  Min amount out: {}", route.min_amount_out);
-                    msg!("  ✅ Raydium swap executed successfully");
-                },
-                DexId::Jupiter => {
-                    msg!("🪐 Jupiter swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    msg!("  Min amount out: {}", route.min_amount_out);
-                    msg!("  ✅ Jupiter swap executed successfully");
-                },
+            emit!(ArbitrageStateInitialized {
+                state_id,
+                authority: arbitrage_state.authority,
+                min_execution_interval,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Arbitrage state initialized for authority: {}", arbitrage_state.authority);
+            Ok(())
+        }
+
+        /// Bring a state account written by an older program version up to the current layout:
+        /// realloc it to `ArbitrageState::LEN` and fill every newer field with its default
+        pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+            let state_info = ctx.accounts.arbitrage_state.to_account_info();
+            let (from_version, migrated) = {
+                let data = state_info.try_borrow_data()?;
+                require!(
+                    data.len() >= 8 && data[..8] == *ArbitrageState::DISCRIMINATOR,
+                    ArbitrageError::AccountValidationFailed
+                );
+                if data.len() == ArbitrageState::LEGACY_LEN {
+                    (0, ArbitrageState::from_legacy(&data)?)
+                } else {
+                    match data.get(8).copied() {
+                        Some(ArbitrageState::CURRENT_VERSION) => return err!(ArbitrageError::StateAlreadyMigrated),
+                        _ => return err!(ArbitrageError::UnsupportedStateVersion),
+                    }
+                }
+            };
+
+            require_keys_eq!(migrated.authority, ctx.accounts.authority.key(), ArbitrageError::Unauthorized);
+            let expected_address = Pubkey::create_program_address(
+                &[b"arbitrage_state", migrated.state_id.as_ref(), &[migrated.bump]],
+                &crate::ID,
+            )
+            .map_err(|_| ArbitrageError::AccountValidationFailed)?;
+            require_keys_eq!(state_info.key(), expected_address, ArbitrageError::AccountValidationFailed);
+
+            // Top up rent for the larger account, then grow it in place
+            let rent_shortfall = Rent::get()?
+                .minimum_balance(ArbitrageState::LEN)
+                .saturating_sub(state_info.lamports());
+            if rent_shortfall > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: state_info.clone(),
+                        },
+                    ),
+                    rent_shortfall,
+                )?;
             }
+            state_info.resize(ArbitrageState::LEN)?;
+
+            let mut data = state_info.try_borrow_mut_data()?;
+            migrated.try_serialize(&mut &mut data[..])?;
+
+            emit!(StateMigrated {
+                arbitrage_state: state_info.key(),
+                from_version,
+                to_version: ArbitrageState::CURRENT_VERSION,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Arbitrage state migrated from version {} to {}", from_version, ArbitrageState::CURRENT_VERSION);
+            Ok(())
         }
 
-        // Update state after successful execution
-        arbitrage_state.last_execution_time = current_time;
-        arbitrage_state.total_trades += 1;
-        arbitrage_state.total_profit += expected_profit;
+        pub fn initialize_profit_vault(ctx: Context<InitializeProfitVault>) -> Result<()> {
+            emit!(ProfitVaultInitialized {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                mint: ctx.accounts.mint.key(),
+                profit_vault: ctx.accounts.profit_vault.key(),
+                timestamp: Clock::get()?.unix_timestamp,
+            });
 
-        emit!(ArbitrageExecuted {
-            user: ctx.accounts.user.key(),
-            profit: expected_profit,
-            routes: routes.len() as u8,
-            timestamp: current_time,
-        });
+            msg!("Profit vault initialized for mint: {}", ctx.accounts.mint.key());
+            Ok(())
+        }
 
-        msg!("Arbitrage sequence completed successfully");
-        Ok(())
-    }
+        pub fn initialize_mint_stats(ctx: Context<InitializeMintStats>) -> Result<()> {
+            let mint_stats = &mut ctx.accounts.mint_stats;
+            mint_stats.arbitrage_state = ctx.accounts.arbitrage_state.key();
+            mint_stats.mint = ctx.accounts.mint.key();
+            mint_stats.bump = ctx.bumps.mint_stats;
 
-    // 🌊 NEW! Real Orca Whirlpool CPI Integration
-    pub fn orca_swap(
-        ctx: Context<OrcaSwap>,
-        amount: u64,
-        other_amount_threshold: u64,
-        _sqrt_price_limit: u128,
-        _amount_specified_is_input: bool,
-        a_to_b: bool,
-    ) -> Result<()> {
-        // Safety checks first
-        require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
-        require!(amount > 0, ArbitrageError::InvalidAmount);
-
-        msg!("🌊 Executing Orca Whirlpool swap");
-        msg!("  Amount: {} | Min output: {} | A->B: {}", amount, other_amount_threshold, a_to_b);
-
-        // Validate accounts before CPI
-        ctx.accounts.validate_accounts()?;
-
-        msg!("🌊 Executing REAL Orca Whirlpool swap via CPI");
-        msg!("  Whirlpool: {}", ctx.accounts.whirlpool.key());
-        msg!("  Amount: {} | Min output: {} | A->B: {}", amount, other_amount_threshold, a_to_b);
-        msg!("  Token A: {} | Token B: {}", ctx.accounts.token_owner_account_a.key(), ctx.accounts.token_owner_account_b.key());
-        
-        // Build CPI instruction to Orca Whirlpool
-        let swap_instruction = whirlpool_swap::SwapInstruction {
-            amount,
-            other_amount_threshold,
-            sqrt_price_limit: _sqrt_price_limit,
-            amount_specified_is_input: _amount_specified_is_input,
-            a_to_b,
-        };
+            msg!("Mint stats initialized for mint: {}", mint_stats.mint);
+            Ok(())
+        }
 
-        // Execute the swap via direct invoke
-        let swap_ix = Instruction {
-            program_id: WHIRLPOOL_PROGRAM_ID,
-            accounts: vec![
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.user.key(), true),
-                AccountMeta::new(ctx.accounts.whirlpool.key(), false),
-                AccountMeta::new(ctx.accounts.token_owner_account_a.key(), false),
-                AccountMeta::new(ctx.accounts.token_vault_a.key(), false),
-                AccountMeta::new(ctx.accounts.token_owner_account_b.key(), false),
-                AccountMeta::new(ctx.accounts.token_vault_b.key(), false),
-                AccountMeta::new(ctx.accounts.tick_array_0.key(), false),
-                AccountMeta::new(ctx.accounts.tick_array_1.key(), false),
-                AccountMeta::new(ctx.accounts.tick_array_2.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.oracle.key(), false),
-            ],
-            data: {
-                let mut data = vec![0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8]; // Orca swap discriminator
-                data.append(&mut swap_instruction.try_to_vec().unwrap());
-                data
-            },
-        };
+        pub fn flash_arbitrage<'info>(
+            ctx: Context<'_, '_, 'info, 'info, FlashArbitrage<'info>>,
+            routes: Vec<SwapRoute>,
+            min_profit: u64,
+            amount_mode: RouteAmountMode,
+            max_slot: Option<u64>,
+            land_failed_trade: bool,
+        ) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
 
-        msg!("📞 Calling Orca Whirlpool program...");
-        
-        // Execute the swap via invoke
-        invoke(
-            &swap_ix,
-            &[
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.user.to_account_info(),
-                ctx.accounts.whirlpool.to_account_info(),
-                ctx.accounts.token_owner_account_a.to_account_info(),
-                ctx.accounts.token_vault_a.to_account_info(),
-                ctx.accounts.token_owner_account_b.to_account_info(),
-                ctx.accounts.token_vault_b.to_account_info(),
-                ctx.accounts.tick_array_0.to_account_info(),
-                ctx.accounts.tick_array_1.to_account_info(),
-                ctx.accounts.tick_array_2.to_account_info(),
-                ctx.accounts.oracle.to_account_info(),
-            ],
-        )?;
+            // Safety checks
+            check_opportunity_deadline(max_slot)?;
+            require!(!arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            validate_route_chain(&routes)?;
+            require!(routes.len() <= arbitrage_state.max_hops as usize, ArbitrageError::TooManyHops);
+            require!(min_profit > 0, ArbitrageError::InvalidAmount);
+            arbitrage_state.check_notional_limit(&routes[0].input_mint, routes[0].amount_in)?;
+            require_keys_eq!(
+                ctx.accounts.user_token_account.mint,
+                routes[0].input_mint,
+                ArbitrageError::AccountValidationFailed
+            );
 
-        msg!("✅ Orca CPI swap completed successfully!");
+            // Rate limiting check
+            let current_time = Clock::get()?.unix_timestamp;
+            let time_since_last = current_time - arbitrage_state.last_execution_time;
+            require!(
+                time_since_last >= arbitrage_state.min_execution_interval,
+                ArbitrageError::ExecutionTooFrequent
+            );
 
-        // Update state after validation
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        arbitrage_state.total_trades += 1;
-        arbitrage_state.last_execution_time = Clock::get()?.unix_timestamp;
+            msg!("Starting arbitrage sequence with {} routes", routes.len());
+            msg!("Minimum profit: {} tokens", min_profit);
 
-        emit!(OrcaSwapExecuted {
-            user: ctx.accounts.user.key(),
-            whirlpool: ctx.accounts.whirlpool.key(),
-            amount,
-            other_amount_threshold,
-            a_to_b,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+            // Snapshot the input-mint balance before the first hop
+            let balance_before = ctx.accounts.user_token_account.amount;
 
-        msg!("✅ Orca swap completed successfully");
-        Ok(())
-    }
+            // Execute each swap route in sequence, consuming its hop accounts from remaining_accounts
+            execute_routes(
+                &routes,
+                amount_mode,
+                ctx.remaining_accounts,
+                &ctx.accounts.arbitrage_state,
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.user_token_account.key(),
+                &ctx.accounts.spl_token_program.to_account_info(),
+            )?;
 
-    pub fn flash_loan_arbitrage(
-        ctx: Context<FlashLoanArbitrage>,
-        flash_loan_amount: u64,
-        routes: Vec<SwapRoute>,
-        expected_profit: u64,
-    ) -> Result<()> {
-        // Safety checks first
-        require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
-        require!(!routes.is_empty(), ArbitrageError::EmptyRoutes);
-        require!(routes.len() <= 4, ArbitrageError::TooManyHops);
-        require!(expected_profit > 0, ArbitrageError::InvalidAmount);
-        require!(flash_loan_amount > 0, ArbitrageError::InvalidAmount);
-
-        msg!("🏦 Starting REAL flash loan arbitrage");
-        msg!("  Flash loan amount: {} tokens", flash_loan_amount);
-        msg!("  Expected profit: {} tokens", expected_profit);
-        msg!("  Routes: {}", routes.len());
-
-        // Step 1: Initiate flash loan from Solend
-        msg!("📋 Initiating flash loan from Solend...");
-        
-        let flash_loan_ix = create_solend_flash_loan_instruction(
-            &ctx.accounts.user.key(),
-            &ctx.accounts.reserve.key(),
-            &ctx.accounts.reserve_liquidity_supply.key(),
-            flash_loan_amount,
-        )?;
-
-        // Execute flash loan
-        invoke(
-            &flash_loan_ix,
-            &[
-                ctx.accounts.user.to_account_info(),
-                ctx.accounts.reserve.to_account_info(),
-                ctx.accounts.reserve_liquidity_supply.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-            ],
-        )?;
-
-        msg!("✅ Flash loan borrowed: {} tokens", flash_loan_amount);
-
-        // Step 2: Execute arbitrage sequence with borrowed funds
-        for (i, route) in routes.iter().enumerate() {
-            msg!("Executing arbitrage route {}/{}", i + 1, routes.len());
-            
-            match route.dex_id {
-                DexId::Orca => {
-                    msg!("🌊 Flash loan Orca swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    
-                    // Execute real Orca swap with flash loan funds
-                    execute_orca_swap_with_flash_loan(
-                        &ctx.accounts.user.key(),
-                        route.amount_in,
-                        route.min_amount_out,
+            // Verify realized profit from the balance delta after the last hop
+            ctx.accounts.user_token_account.reload()?;
+            let realized_pnl = calculate_realized_pnl(
+                balance_before,
+                ctx.accounts.user_token_account.amount,
+                &ctx.accounts.input_mint.to_account_info(),
+            )?;
+            ctx.accounts
+                .mint_stats
+                .record_arbitrage(routes[0].amount_in, realized_pnl)?;
+            if !meets_min_profit(realized_pnl, min_profit) {
+                // Missing min_profit reverts unless the caller opted in to landing the failure
+                require!(land_failed_trade, ArbitrageError::InsufficientProfit);
+                // A landed shortfall can still be a gain, which is swept like any other profit
+                if realized_pnl > 0 {
+                    sweep_profit_to_vault(
+                        &ctx.accounts.user_token_account,
+                        &ctx.accounts.profit_vault,
+                        &ctx.accounts.input_mint,
+                        &ctx.accounts.user,
+                        &ctx.accounts.token_program,
+                        balance_before,
                     )?;
+                }
+                return record_failed_trade(
+                    &mut ctx.accounts.arbitrage_state,
+                    ctx.accounts.user.key(),
+                    realized_pnl,
+                    min_profit,
+                    current_time,
+                );
+            }
+            let realized_profit = realized_pnl as u64;
+            ctx.accounts
+                .arbitrage_state
+                .check_min_profit_bps(realized_profit, routes[0].amount_in)?;
+
+            // Sweep the realized profit into the program-owned vault
+            sweep_profit_to_vault(
+                &ctx.accounts.user_token_account,
+                &ctx.accounts.profit_vault,
+                &ctx.accounts.input_mint,
+                &ctx.accounts.user,
+                &ctx.accounts.token_program,
+                balance_before,
+            )?;
+
+            // Update state after successful execution
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.last_execution_time = current_time;
+            arbitrage_state.record_trade_totals(realized_pnl)?;
+            if let Some(reason) = arbitrage_state.record_trade_result(realized_pnl, true, current_time)? {
+                emit_circuit_breaker_tripped(arbitrage_state, reason, current_time);
+            }
+
+            emit!(ArbitrageExecuted {
+                user: ctx.accounts.user.key(),
+                profit: realized_profit,
+                routes: routes.len() as u8,
+                timestamp: current_time,
+            });
+
+            msg!("Arbitrage sequence completed successfully (realized profit: {})", realized_profit);
+            Ok(())
+        }
+
+        // 🌊 NEW! Real Orca Whirlpool CPI Integration
+        pub fn orca_swap(
+            ctx: Context<OrcaSwap>,
+            amount: u64,
+            other_amount_threshold: u64,
+            _sqrt_price_limit: u128,
+            _amount_specified_is_input: bool,
+            a_to_b: bool,
+            max_slot: Option<u64>,
+        ) -> Result<()> {
+            // Safety checks first
+            check_opportunity_deadline(max_slot)?;
+            require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            require!(amount > 0, ArbitrageError::InvalidAmount);
+
+            msg!("🌊 Executing Orca Whirlpool swap");
+            msg!("  Amount: {} | Min output: {} | A->B: {}", amount, other_amount_threshold, a_to_b);
+
+            // Validate accounts before CPI
+            ctx.accounts.validate_accounts()?;
+
+            // A single swap has no profit or hop count; only the input notional cap applies
+            let (mint_a, mint_b) = whirlpool_swap::read_pool_mints(&ctx.accounts.whirlpool)?;
+            let input_mint = if a_to_b { mint_a } else { mint_b };
+            let input_amount = if _amount_specified_is_input { amount } else { other_amount_threshold };
+            ctx.accounts.arbitrage_state.check_notional_limit(&input_mint, input_amount)?;
+
+            msg!("🌊 Executing REAL Orca Whirlpool swap via CPI");
+            msg!("  Whirlpool: {}", ctx.accounts.whirlpool.key());
+            msg!("  Amount: {} | Min output: {} | A->B: {}", amount, other_amount_threshold, a_to_b);
+            msg!("  Token A: {} | Token B: {}", ctx.accounts.token_owner_account_a.key(), ctx.accounts.token_owner_account_b.key());
+
+            // Build CPI instruction to Orca Whirlpool
+            let swap_instruction = whirlpool_swap::SwapInstruction {
+                amount,
+                other_amount_threshold,
+                sqrt_price_limit: _sqrt_price_limit,
+                amount_specified_is_input: _amount_specified_is_input,
+                a_to_b,
+            };
+
+            // Execute the swap via direct invoke
+            let swap_ix = Instruction {
+                program_id: WHIRLPOOL_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+                    AccountMeta::new_readonly(ctx.accounts.user.key(), true),
+                    AccountMeta::new(ctx.accounts.whirlpool.key(), false),
+                    AccountMeta::new(ctx.accounts.token_owner_account_a.key(), false),
+                    AccountMeta::new(ctx.accounts.token_vault_a.key(), false),
+                    AccountMeta::new(ctx.accounts.token_owner_account_b.key(), false),
+                    AccountMeta::new(ctx.accounts.token_vault_b.key(), false),
+                    AccountMeta::new(ctx.accounts.tick_array_0.key(), false),
+                    AccountMeta::new(ctx.accounts.tick_array_1.key(), false),
+                    AccountMeta::new(ctx.accounts.tick_array_2.key(), false),
+                    AccountMeta::new_readonly(ctx.accounts.oracle.key(), false),
+                ],
+                data: swap_instruction.data()?,
+            };
+
+            let balance_a_before = token_account_amount(&ctx.accounts.token_owner_account_a)?;
+            let balance_b_before = token_account_amount(&ctx.accounts.token_owner_account_b)?;
+
+            msg!("📞 Calling Orca Whirlpool program...");
+
+            // Execute the swap via invoke
+            invoke(
+                &swap_ix,
+                &[
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.whirlpool.to_account_info(),
+                    ctx.accounts.token_owner_account_a.to_account_info(),
+                    ctx.accounts.token_vault_a.to_account_info(),
+                    ctx.accounts.token_owner_account_b.to_account_info(),
+                    ctx.accounts.token_vault_b.to_account_info(),
+                    ctx.accounts.tick_array_0.to_account_info(),
+                    ctx.accounts.tick_array_1.to_account_info(),
+                    ctx.accounts.tick_array_2.to_account_info(),
+                    ctx.accounts.oracle.to_account_info(),
+                ],
+            )?;
+
+            msg!("✅ Orca CPI swap completed successfully!");
+
+            let accounts = &mut *ctx.accounts;
+            record_pool_swap_volume(
+                &accounts.token_owner_account_a,
+                &accounts.token_owner_account_b,
+                &mut accounts.mint_stats_a,
+                &mut accounts.mint_stats_b,
+                (balance_a_before, balance_b_before),
+                a_to_b,
+            )?;
+
+            // Update state after validation
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.total_trades += 1;
+            arbitrage_state.last_execution_time = Clock::get()?.unix_timestamp;
+
+            emit!(OrcaSwapExecuted {
+                user: ctx.accounts.user.key(),
+                whirlpool: ctx.accounts.whirlpool.key(),
+                amount,
+                other_amount_threshold,
+                a_to_b,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("✅ Orca swap completed successfully");
+            Ok(())
+        }
+
+        // 🌊 Orca Whirlpool swap_v2 (Token-2022 aware)
+        pub fn orca_swap_v2(
+            ctx: Context<OrcaSwapV2>,
+            amount: u64,
+            other_amount_threshold: u64,
+            sqrt_price_limit: u128,
+            amount_specified_is_input: bool,
+            a_to_b: bool,
+        ) -> Result<()> {
+            // Safety checks first
+            require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            require!(amount > 0, ArbitrageError::InvalidAmount);
+
+            // Validate accounts before CPI
+            ctx.accounts.validate_accounts()?;
+
+            // A single swap has no profit or hop count; only the input notional cap applies
+            let input_mint = if a_to_b { ctx.accounts.token_mint_a.key() } else { ctx.accounts.token_mint_b.key() };
+            let input_amount = if amount_specified_is_input { amount } else { other_amount_threshold };
+            ctx.accounts.arbitrage_state.check_notional_limit(&input_mint, input_amount)?;
+
+            msg!("🌊 Executing REAL Orca Whirlpool swap_v2 via CPI");
+            msg!("  Whirlpool: {}", ctx.accounts.whirlpool.key());
+            msg!("  Amount: {} | Threshold: {} | A->B: {}", amount, other_amount_threshold, a_to_b);
+
+            let swap_ix = whirlpool_swap::swap_v2_instruction(
+                &whirlpool_swap::SwapV2Keys {
+                    token_program_a: ctx.accounts.token_program_a.key(),
+                    token_program_b: ctx.accounts.token_program_b.key(),
+                    memo_program: ctx.accounts.memo_program.key(),
+                    token_authority: ctx.accounts.user.key(),
+                    whirlpool: ctx.accounts.whirlpool.key(),
+                    token_mint_a: ctx.accounts.token_mint_a.key(),
+                    token_mint_b: ctx.accounts.token_mint_b.key(),
+                    token_owner_account_a: ctx.accounts.token_owner_account_a.key(),
+                    token_vault_a: ctx.accounts.token_vault_a.key(),
+                    token_owner_account_b: ctx.accounts.token_owner_account_b.key(),
+                    token_vault_b: ctx.accounts.token_vault_b.key(),
+                    tick_array_0: ctx.accounts.tick_array_0.key(),
+                    tick_array_1: ctx.accounts.tick_array_1.key(),
+                    tick_array_2: ctx.accounts.tick_array_2.key(),
+                    oracle: ctx.accounts.oracle.key(),
                 },
-                DexId::Raydium => {
-                    msg!("⚡ Flash loan Raydium swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    
-                    // Execute real Raydium swap with flash loan funds  
-                    execute_raydium_swap_with_flash_loan(
-                        &ctx.accounts.user.key(),
-                        route.amount_in,
-                        route.min_amount_out,
-                    )?;
+                &whirlpool_swap::SwapV2Instruction {
+                    amount,
+                    other_amount_threshold,
+                    sqrt_price_limit,
+                    amount_specified_is_input,
+                    a_to_b,
+                    remaining_accounts_info: None,
                 },
-                DexId::Jupiter => {
-                    msg!("🪐 Flash loan Jupiter swap: {} → {} (amount: {})", 
-                         route.input_mint, route.output_mint, route.amount_in);
-                    
-                    // Execute Jupiter swap with flash loan funds
-                    execute_jupiter_swap_with_flash_loan(
-                        &ctx.accounts.user.key(),
-                        route.amount_in,
-                        route.min_amount_out,
-                    )?;
+            )?;
+
+            let balance_a_before = token_account_amount(&ctx.accounts.token_owner_account_a)?;
+            let balance_b_before = token_account_amount(&ctx.accounts.token_owner_account_b)?;
+
+            msg!("📞 Calling Orca Whirlpool program...");
+
+            invoke(
+                &swap_ix,
+                &[
+                    ctx.accounts.token_program_a.to_account_info(),
+                    ctx.accounts.token_program_b.to_account_info(),
+                    ctx.accounts.memo_program.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.whirlpool.to_account_info(),
+                    ctx.accounts.token_mint_a.to_account_info(),
+                    ctx.accounts.token_mint_b.to_account_info(),
+                    ctx.accounts.token_owner_account_a.to_account_info(),
+                    ctx.accounts.token_vault_a.to_account_info(),
+                    ctx.accounts.token_owner_account_b.to_account_info(),
+                    ctx.accounts.token_vault_b.to_account_info(),
+                    ctx.accounts.tick_array_0.to_account_info(),
+                    ctx.accounts.tick_array_1.to_account_info(),
+                    ctx.accounts.tick_array_2.to_account_info(),
+                    ctx.accounts.oracle.to_account_info(),
+                ],
+            )?;
+
+            let accounts = &mut *ctx.accounts;
+            record_pool_swap_volume(
+                &accounts.token_owner_account_a,
+                &accounts.token_owner_account_b,
+                &mut accounts.mint_stats_a,
+                &mut accounts.mint_stats_b,
+                (balance_a_before, balance_b_before),
+                a_to_b,
+            )?;
+
+            // Update state after validation
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.total_trades += 1;
+            arbitrage_state.last_execution_time = Clock::get()?.unix_timestamp;
+
+            emit!(OrcaSwapExecuted {
+                user: ctx.accounts.user.key(),
+                whirlpool: ctx.accounts.whirlpool.key(),
+                amount,
+                other_amount_threshold,
+                a_to_b,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("✅ Orca swap_v2 completed successfully");
+            Ok(())
+        }
+
+        // 🌊 Orca Whirlpool two_hop_swap_v2 - two pools in one CPI
+        #[allow(clippy::too_many_arguments)]
+        pub fn orca_two_hop_swap(
+            ctx: Context<OrcaTwoHopSwap>,
+            amount: u64,
+            other_amount_threshold: u64,
+            amount_specified_is_input: bool,
+            a_to_b_one: bool,
+            a_to_b_two: bool,
+            sqrt_price_limit_one: u128,
+            sqrt_price_limit_two: u128,
+        ) -> Result<()> {
+            // Safety checks first
+            require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            require!(amount > 0, ArbitrageError::InvalidAmount);
+
+            // Validate accounts before CPI
+            ctx.accounts.validate_accounts(a_to_b_one, a_to_b_two)?;
+
+            // Output-specified swaps spend at most other_amount_threshold of the input mint
+            let input_amount = if amount_specified_is_input { amount } else { other_amount_threshold };
+            ctx.accounts
+                .arbitrage_state
+                .check_notional_limit(&ctx.accounts.token_mint_input.key(), input_amount)?;
+
+            msg!("🌊 Executing REAL Orca Whirlpool two_hop_swap_v2 via CPI");
+            msg!("  Whirlpools: {} → {}", ctx.accounts.whirlpool_one.key(), ctx.accounts.whirlpool_two.key());
+            msg!("  Amount: {} | Threshold: {}", amount, other_amount_threshold);
+
+            let swap_ix = whirlpool_swap::two_hop_swap_v2_instruction(
+                &whirlpool_swap::TwoHopSwapV2Keys {
+                    whirlpool_one: ctx.accounts.whirlpool_one.key(),
+                    whirlpool_two: ctx.accounts.whirlpool_two.key(),
+                    token_mint_input: ctx.accounts.token_mint_input.key(),
+                    token_mint_intermediate: ctx.accounts.token_mint_intermediate.key(),
+                    token_mint_output: ctx.accounts.token_mint_output.key(),
+                    token_program_input: ctx.accounts.token_program_input.key(),
+                    token_program_intermediate: ctx.accounts.token_program_intermediate.key(),
+                    token_program_output: ctx.accounts.token_program_output.key(),
+                    token_owner_account_input: ctx.accounts.token_owner_account_input.key(),
+                    token_vault_one_input: ctx.accounts.token_vault_one_input.key(),
+                    token_vault_one_intermediate: ctx.accounts.token_vault_one_intermediate.key(),
+                    token_vault_two_intermediate: ctx.accounts.token_vault_two_intermediate.key(),
+                    token_vault_two_output: ctx.accounts.token_vault_two_output.key(),
+                    token_owner_account_output: ctx.accounts.token_owner_account_output.key(),
+                    token_authority: ctx.accounts.user.key(),
+                    tick_array_one_0: ctx.accounts.tick_array_one_0.key(),
+                    tick_array_one_1: ctx.accounts.tick_array_one_1.key(),
+                    tick_array_one_2: ctx.accounts.tick_array_one_2.key(),
+                    tick_array_two_0: ctx.accounts.tick_array_two_0.key(),
+                    tick_array_two_1: ctx.accounts.tick_array_two_1.key(),
+                    tick_array_two_2: ctx.accounts.tick_array_two_2.key(),
+                    oracle_one: ctx.accounts.oracle_one.key(),
+                    oracle_two: ctx.accounts.oracle_two.key(),
+                    memo_program: ctx.accounts.memo_program.key(),
+                },
+                &whirlpool_swap::TwoHopSwapV2Instruction {
+                    amount,
+                    other_amount_threshold,
+                    amount_specified_is_input,
+                    a_to_b_one,
+                    a_to_b_two,
+                    sqrt_price_limit_one,
+                    sqrt_price_limit_two,
+                    remaining_accounts_info: None,
+                },
+            )?;
+
+            let input_balance_before = token_account_amount(&ctx.accounts.token_owner_account_input)?;
+            let output_balance_before = token_account_amount(&ctx.accounts.token_owner_account_output)?;
+
+            msg!("📞 Calling Orca Whirlpool program...");
+
+            invoke(
+                &swap_ix,
+                &[
+                    ctx.accounts.whirlpool_one.to_account_info(),
+                    ctx.accounts.whirlpool_two.to_account_info(),
+                    ctx.accounts.token_mint_input.to_account_info(),
+                    ctx.accounts.token_mint_intermediate.to_account_info(),
+                    ctx.accounts.token_mint_output.to_account_info(),
+                    ctx.accounts.token_program_input.to_account_info(),
+                    ctx.accounts.token_program_intermediate.to_account_info(),
+                    ctx.accounts.token_program_output.to_account_info(),
+                    ctx.accounts.token_owner_account_input.to_account_info(),
+                    ctx.accounts.token_vault_one_input.to_account_info(),
+                    ctx.accounts.token_vault_one_intermediate.to_account_info(),
+                    ctx.accounts.token_vault_two_intermediate.to_account_info(),
+                    ctx.accounts.token_vault_two_output.to_account_info(),
+                    ctx.accounts.token_owner_account_output.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.tick_array_one_0.to_account_info(),
+                    ctx.accounts.tick_array_one_1.to_account_info(),
+                    ctx.accounts.tick_array_one_2.to_account_info(),
+                    ctx.accounts.tick_array_two_0.to_account_info(),
+                    ctx.accounts.tick_array_two_1.to_account_info(),
+                    ctx.accounts.tick_array_two_2.to_account_info(),
+                    ctx.accounts.oracle_one.to_account_info(),
+                    ctx.accounts.oracle_two.to_account_info(),
+                    ctx.accounts.memo_program.to_account_info(),
+                ],
+            )?;
+
+            let accounts = &mut *ctx.accounts;
+            record_swap_volume(
+                &accounts.token_owner_account_input,
+                &accounts.token_owner_account_output,
+                &mut accounts.mint_stats_input,
+                &mut accounts.mint_stats_output,
+                input_balance_before,
+                output_balance_before,
+            )?;
+
+            // Update state after validation
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.total_trades += 1;
+            arbitrage_state.last_execution_time = Clock::get()?.unix_timestamp;
+
+            emit!(OrcaTwoHopSwapExecuted {
+                user: ctx.accounts.user.key(),
+                whirlpool_one: ctx.accounts.whirlpool_one.key(),
+                whirlpool_two: ctx.accounts.whirlpool_two.key(),
+                amount,
+                other_amount_threshold,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("✅ Orca two-hop swap completed successfully");
+            Ok(())
+        }
+
+        // ⚡ Real Raydium AMM v4 CPI Integration
+        pub fn raydium_swap(
+            ctx: Context<RaydiumSwap>,
+            amount_in: u64,
+            minimum_amount_out: u64,
+        ) -> Result<()> {
+            // Safety checks first
+            require!(!ctx.accounts.arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            require!(amount_in > 0, ArbitrageError::InvalidAmount);
+
+            // Validate accounts before CPI
+            ctx.accounts.validate_accounts()?;
+
+            let source_mint = token_account_mint(&ctx.accounts.user_source_token_account)?;
+            ctx.accounts.arbitrage_state.check_notional_limit(&source_mint, amount_in)?;
+
+            msg!("⚡ Executing REAL Raydium AMM v4 swap via CPI");
+            msg!("  AMM: {}", ctx.accounts.amm.key());
+            msg!("  Amount in: {} | Min output: {}", amount_in, minimum_amount_out);
+
+            let swap_ix = raydium_amm::swap_base_in_instruction(
+                &raydium_amm::SwapBaseInKeys {
+                    token_program: ctx.accounts.token_program.key(),
+                    amm: ctx.accounts.amm.key(),
+                    amm_authority: ctx.accounts.amm_authority.key(),
+                    amm_open_orders: ctx.accounts.amm_open_orders.key(),
+                    amm_target_orders: ctx.accounts.amm_target_orders.key(),
+                    pool_coin_token_account: ctx.accounts.pool_coin_token_account.key(),
+                    pool_pc_token_account: ctx.accounts.pool_pc_token_account.key(),
+                    serum_program: ctx.accounts.serum_program.key(),
+                    serum_market: ctx.accounts.serum_market.key(),
+                    serum_bids: ctx.accounts.serum_bids.key(),
+                    serum_asks: ctx.accounts.serum_asks.key(),
+                    serum_event_queue: ctx.accounts.serum_event_queue.key(),
+                    serum_coin_vault: ctx.accounts.serum_coin_vault.key(),
+                    serum_pc_vault: ctx.accounts.serum_pc_vault.key(),
+                    serum_vault_signer: ctx.accounts.serum_vault_signer.key(),
+                    user_source_token_account: ctx.accounts.user_source_token_account.key(),
+                    user_destination_token_account: ctx.accounts.user_destination_token_account.key(),
+                    user_owner: ctx.accounts.user.key(),
                 },
+                amount_in,
+                minimum_amount_out,
+            );
+
+            let source_balance_before = token_account_amount(&ctx.accounts.user_source_token_account)?;
+            let destination_balance_before = token_account_amount(&ctx.accounts.user_destination_token_account)?;
+
+            msg!("📞 Calling Raydium AMM program...");
+
+            invoke(
+                &swap_ix,
+                &[
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.amm.to_account_info(),
+                    ctx.accounts.amm_authority.to_account_info(),
+                    ctx.accounts.amm_open_orders.to_account_info(),
+                    ctx.accounts.amm_target_orders.to_account_info(),
+                    ctx.accounts.pool_coin_token_account.to_account_info(),
+                    ctx.accounts.pool_pc_token_account.to_account_info(),
+                    ctx.accounts.serum_program.to_account_info(),
+                    ctx.accounts.serum_market.to_account_info(),
+                    ctx.accounts.serum_bids.to_account_info(),
+                    ctx.accounts.serum_asks.to_account_info(),
+                    ctx.accounts.serum_event_queue.to_account_info(),
+                    ctx.accounts.serum_coin_vault.to_account_info(),
+                    ctx.accounts.serum_pc_vault.to_account_info(),
+                    ctx.accounts.serum_vault_signer.to_account_info(),
+                    ctx.accounts.user_source_token_account.to_account_info(),
+                    ctx.accounts.user_destination_token_account.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.raydium_amm_program.to_account_info(),
+                ],
+            )?;
+
+            msg!("✅ Raydium CPI swap completed successfully!");
+
+            let accounts = &mut *ctx.accounts;
+            record_swap_volume(
+                &accounts.user_source_token_account,
+                &accounts.user_destination_token_account,
+                &mut accounts.mint_stats_source,
+                &mut accounts.mint_stats_destination,
+                source_balance_before,
+                destination_balance_before,
+            )?;
+
+            // Update state after validation
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.total_trades += 1;
+            arbitrage_state.last_execution_time = Clock::get()?.unix_timestamp;
+
+            emit!(RaydiumSwapExecuted {
+                user: ctx.accounts.user.key(),
+                amm: ctx.accounts.amm.key(),
+                amount_in,
+                minimum_amount_out,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("✅ Raydium swap completed successfully");
+            Ok(())
+        }
+
+        pub fn flash_loan_arbitrage<'info>(
+            ctx: Context<'_, '_, 'info, 'info, FlashLoanArbitrage<'info>>,
+            flash_loan_amount: u64,
+            routes: Vec<SwapRoute>,
+            min_profit: u64,
+            amount_mode: RouteAmountMode,
+            max_slot: Option<u64>,
+            land_failed_trade: bool,
+        ) -> Result<()> {
+            // Safety checks first
+            check_opportunity_deadline(max_slot)?;
+            let arbitrage_state = &ctx.accounts.arbitrage_state;
+            require!(!arbitrage_state.is_paused, ArbitrageError::BotPaused);
+            validate_route_chain(&routes)?;
+            require!(routes.len() <= arbitrage_state.max_hops as usize, ArbitrageError::TooManyHops);
+            require!(min_profit > 0, ArbitrageError::InvalidAmount);
+            require!(flash_loan_amount > 0, ArbitrageError::InvalidAmount);
+            arbitrage_state.check_notional_limit(&routes[0].input_mint, flash_loan_amount)?;
+            arbitrage_state.check_notional_limit(&routes[0].input_mint, routes[0].amount_in)?;
+            require_keys_eq!(
+                ctx.accounts.user_token_account.mint,
+                routes[0].input_mint,
+                ArbitrageError::AccountValidationFailed
+            );
+
+            msg!("🏦 Starting REAL flash loan arbitrage");
+            msg!("  Flash loan amount: {} tokens", flash_loan_amount);
+            msg!("  Minimum profit: {} tokens", min_profit);
+            msg!("  Routes: {}", routes.len());
+
+            // Step 1: Solend refuses flash loans under CPI, so the bot places FlashBorrow and
+            // FlashRepay as top-level instructions around this one; confirm that pairing
+            solend::verify_flash_loan_pairing(
+                &ctx.accounts.instructions_sysvar.to_account_info(),
+                &ctx.accounts.reserve.key(),
+                &ctx.accounts.user_token_account.key(),
+                &ctx.accounts.user.key(),
+                flash_loan_amount,
+            )?;
+            let flash_loan_fee = calculate_flash_loan_fee(&ctx.accounts.reserve.to_account_info(), flash_loan_amount)?;
+
+            // Snapshot the input-mint balance, which already holds the borrowed principal
+            let balance_before = ctx.accounts.user_token_account.amount;
+            msg!("✅ Flash loan borrowed: {} tokens", flash_loan_amount);
+
+            // Step 2: Execute arbitrage sequence with borrowed funds
+            execute_routes(
+                &routes,
+                amount_mode,
+                ctx.remaining_accounts,
+                &ctx.accounts.arbitrage_state,
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.user_token_account.key(),
+                &ctx.accounts.spl_token_program.to_account_info(),
+            )?;
+
+            // Step 3: The trailing FlashRepay returns the principal plus Solend's fee, so leave
+            // the fee in the user account and count it against the profit
+            let repay_reserve = balance_before
+                .checked_add(flash_loan_fee)
+                .ok_or(ArbitrageError::ArithmeticError)?;
+            msg!("💰 Leaving {} tokens for the flash loan repay (fee: {})",
+                 flash_loan_amount, flash_loan_fee);
+
+            // Step 4: Verify realized profit (net of the flash loan fee) from the balance delta
+            ctx.accounts.user_token_account.reload()?;
+            let current_time = Clock::get()?.unix_timestamp;
+            let realized_pnl = calculate_realized_pnl(
+                balance_before,
+                ctx.accounts.user_token_account.amount.saturating_sub(flash_loan_fee),
+                &ctx.accounts.input_mint.to_account_info(),
+            )?;
+            ctx.accounts
+                .mint_stats
+                .record_arbitrage(routes[0].amount_in, realized_pnl)?;
+            if !meets_min_profit(realized_pnl, min_profit) {
+                // Missing min_profit reverts unless the caller opted in to landing the failure
+                require!(land_failed_trade, ArbitrageError::InsufficientProfit);
+                // A landed shortfall can still be a gain, which is swept like any other profit
+                if realized_pnl > 0 {
+                    sweep_profit_to_vault(
+                        &ctx.accounts.user_token_account,
+                        &ctx.accounts.profit_vault,
+                        &ctx.accounts.input_mint,
+                        &ctx.accounts.user,
+                        &ctx.accounts.token_program,
+                        repay_reserve,
+                    )?;
+                }
+                return record_failed_trade(
+                    &mut ctx.accounts.arbitrage_state,
+                    ctx.accounts.user.key(),
+                    realized_pnl,
+                    min_profit,
+                    current_time,
+                );
+            }
+            let actual_profit = realized_pnl as u64;
+            ctx.accounts
+                .arbitrage_state
+                .check_min_profit_bps(actual_profit, flash_loan_amount)?;
+
+            // Step 5: Sweep the realized profit into the program-owned vault
+            sweep_profit_to_vault(
+                &ctx.accounts.user_token_account,
+                &ctx.accounts.profit_vault,
+                &ctx.accounts.input_mint,
+                &ctx.accounts.user,
+                &ctx.accounts.token_program,
+                repay_reserve,
+            )?;
+
+            // Update state after all operations complete
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.last_execution_time = current_time;
+            arbitrage_state.record_trade_totals(realized_pnl)?;
+            if let Some(reason) = arbitrage_state.record_trade_result(realized_pnl, true, current_time)? {
+                emit_circuit_breaker_tripped(arbitrage_state, reason, current_time);
             }
+
+            emit!(FlashLoanArbitrageExecuted {
+                user: ctx.accounts.user.key(),
+                flash_loan_amount,
+                profit: actual_profit,
+                routes: routes.len() as u8,
+                timestamp: current_time,
+            });
+
+            msg!("✅ Flash loan arbitrage completed successfully!");
+            msg!("💰 Net profit: {} tokens", actual_profit);
+            Ok(())
         }
-        
-        // Step 3: Repay flash loan + fees
-        let flash_loan_fee = calculate_flash_loan_fee(flash_loan_amount);
-        let repay_amount = flash_loan_amount + flash_loan_fee;
-        
-        msg!("💰 Repaying flash loan: {} tokens (fee: {})", repay_amount, flash_loan_fee);
-        
-        let repay_ix = create_solend_flash_loan_repay_instruction(
-            &ctx.accounts.user.key(),
-            &ctx.accounts.reserve.key(),
-            repay_amount,
-        )?;
-
-        invoke(
-            &repay_ix,
-            &[
-                ctx.accounts.user.to_account_info(),
-                ctx.accounts.reserve.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-            ],
-        )?;
-
-        // Step 4: Calculate and verify profit
-        let actual_profit = expected_profit.saturating_sub(flash_loan_fee);
-        require!(actual_profit > 0, ArbitrageError::InsufficientProfit);
-
-        // Update state after all operations complete
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        let current_time = Clock::get()?.unix_timestamp;
-        arbitrage_state.last_execution_time = current_time;
-        arbitrage_state.total_trades += 1;
-        arbitrage_state.total_profit += actual_profit;
-
-        emit!(FlashLoanArbitrageExecuted {
-            user: ctx.accounts.user.key(),
-            flash_loan_amount,
-            profit: actual_profit,
-            routes: routes.len() as u8,
-            timestamp: current_time,
-        });
-
-        msg!("✅ Flash loan arbitrage completed successfully!");
-        msg!("💰 Net profit: {} tokens", actual_profit);
-        Ok(())
-    }
 
-    pub fn pause_bot(ctx: Context<PauseBot>) -> Result<()> {
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        arbitrage_state.is_paused = true;
+        pub fn pause_bot(ctx: Context<PauseBot>) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.is_paused = true;
 
-        emit!(BotPaused {
-            authority: ctx.accounts.authority.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+            emit!(BotPaused {
+                authority: ctx.accounts.authority.key(),
+                timestamp: Clock::get()?.unix_timestamp,
+            });
 
-        msg!("Bot paused by authority: {}", ctx.accounts.authority.key());
-        Ok(())
-    }
+            msg!("Bot paused by: {}", ctx.accounts.authority.key());
+            Ok(())
+        }
 
-    pub fn resume_bot(ctx: Context<ResumeBot>) -> Result<()> {
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
-        arbitrage_state.is_paused = false;
+        pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.guardian = guardian;
 
-        emit!(BotResumed {
-            authority: ctx.accounts.authority.key(),
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+            emit!(GuardianUpdated {
+                authority: ctx.accounts.authority.key(),
+                guardian,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
 
-        msg!("Bot resumed by authority: {}", ctx.accounts.authority.key());
-        Ok(())
-    }
+            msg!("Guardian updated by authority: {}", ctx.accounts.authority.key());
+            Ok(())
+        }
 
-    pub fn update_bot_config(
-        ctx: Context<UpdateBotConfig>,
-        new_min_execution_interval: Option<i64>,
-    ) -> Result<()> {
-        let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+        pub fn resume_bot(ctx: Context<ResumeBot>) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.is_paused = false;
 
-        if let Some(interval) = new_min_execution_interval {
-            arbitrage_state.min_execution_interval = interval;
+            emit!(BotResumed {
+                authority: ctx.accounts.authority.key(),
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Bot resumed by authority: {}", ctx.accounts.authority.key());
+            Ok(())
         }
 
-        emit!(BotConfigUpdated {
-            authority: ctx.accounts.authority.key(),
-            new_min_execution_interval,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+        /// Applies a config change immediately; only changes that tighten limits are allowed here,
+        /// anything that loosens them must go through `queue_config_change`
+        pub fn update_bot_config(
+            ctx: Context<UpdateBotConfig>,
+            change: ConfigChange,
+        ) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            change.validate()?;
+            require!(!arbitrage_state.is_loosened_by(&change), ArbitrageError::ConfigChangeRequiresTimelock);
 
-        msg!("Bot configuration updated by authority: {}", ctx.accounts.authority.key());
-        Ok(())
-    }
+            arbitrage_state.apply_config_change(&change)?;
 
-    pub fn withdraw_profits(
-        ctx: Context<WithdrawProfits>,
-        amount: u64,
-    ) -> Result<()> {
-        msg!("Withdraw profits called by: {} for amount: {}", ctx.accounts.authority.key(), amount);
-        
-        emit!(ProfitsWithdrawn {
-            authority: ctx.accounts.authority.key(),
-            amount,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
+            emit!(BotConfigUpdated {
+                authority: ctx.accounts.authority.key(),
+                change,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Bot configuration updated by authority: {}", ctx.accounts.authority.key());
+            Ok(())
+        }
+
+        pub fn queue_config_change(
+            ctx: Context<QueueConfigChange>,
+            change: ConfigChange,
+        ) -> Result<()> {
+            change.validate()?;
+
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            let change_id = arbitrage_state.next_config_change_id;
+            arbitrage_state.next_config_change_id = change_id
+                .checked_add(1)
+                .ok_or(ArbitrageError::ArithmeticError)?;
+
+            let current_time = Clock::get()?.unix_timestamp;
+            let executable_at = current_time
+                .checked_add(arbitrage_state.config_timelock)
+                .ok_or(ArbitrageError::ArithmeticError)?;
+
+            let pending_change = &mut ctx.accounts.pending_config_change;
+            pending_change.arbitrage_state = arbitrage_state.key();
+            pending_change.change_id = change_id;
+            pending_change.change = change.clone();
+            pending_change.queued_at = current_time;
+            pending_change.executable_at = executable_at;
+            pending_change.bump = ctx.bumps.pending_config_change;
+
+            emit!(ConfigChangeQueued {
+                arbitrage_state: arbitrage_state.key(),
+                change_id,
+                change,
+                executable_at,
+                timestamp: current_time,
+            });
+
+            msg!("Config change {} queued, executable at {}", change_id, executable_at);
+            Ok(())
+        }
+
+        pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+            let current_time = Clock::get()?.unix_timestamp;
+            let pending_change = &ctx.accounts.pending_config_change;
+            require!(
+                current_time >= pending_change.executable_at,
+                ArbitrageError::ConfigChangeNotReady
+            );
+
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.apply_config_change(&pending_change.change)?;
+
+            emit!(ConfigChangeExecuted {
+                arbitrage_state: arbitrage_state.key(),
+                change_id: pending_change.change_id,
+                change: pending_change.change.clone(),
+                timestamp: current_time,
+            });
+
+            msg!("Config change {} executed", pending_change.change_id);
+            Ok(())
+        }
+
+        pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+            let pending_change = &ctx.accounts.pending_config_change;
+
+            emit!(ConfigChangeCancelled {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                change_id: pending_change.change_id,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Config change {} cancelled", pending_change.change_id);
+            Ok(())
+        }
+
+        pub fn add_operator(ctx: Context<ManageOperators>, operator: Pubkey) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            require!(
+                !arbitrage_state.operators.contains(&operator),
+                ArbitrageError::OperatorAlreadyAdded
+            );
+            require!(
+                arbitrage_state.operators.len() < ArbitrageState::MAX_OPERATORS,
+                ArbitrageError::TooManyOperators
+            );
+            arbitrage_state.operators.push(operator);
+
+            emit!(OperatorAdded {
+                authority: ctx.accounts.authority.key(),
+                operator,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Operator added: {}", operator);
+            Ok(())
+        }
+
+        pub fn remove_operator(ctx: Context<ManageOperators>, operator: Pubkey) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            let position = arbitrage_state
+                .operators
+                .iter()
+                .position(|existing| *existing == operator)
+                .ok_or(ArbitrageError::OperatorNotFound)?;
+            arbitrage_state.operators.swap_remove(position);
+
+            emit!(OperatorRemoved {
+                authority: ctx.accounts.authority.key(),
+                operator,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Operator removed: {}", operator);
+            Ok(())
+        }
+
+        pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            arbitrage_state.pending_authority = Some(new_authority);
+
+            emit!(AuthorityProposed {
+                authority: ctx.accounts.authority.key(),
+                pending_authority: new_authority,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Authority transfer proposed to: {}", new_authority);
+            Ok(())
+        }
+
+        pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+            let arbitrage_state = &mut ctx.accounts.arbitrage_state;
+            let previous_authority = arbitrage_state.authority;
+            arbitrage_state.authority = ctx.accounts.pending_authority.key();
+            arbitrage_state.pending_authority = None;
+
+            emit!(AuthorityTransferred {
+                previous_authority,
+                new_authority: arbitrage_state.authority,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Authority transferred from {} to {}", previous_authority, arbitrage_state.authority);
+            Ok(())
+        }
+
+        pub fn add_allowed_mint(ctx: Context<AddAllowedMint>, mint: Pubkey) -> Result<()> {
+            let allowed_mint = &mut ctx.accounts.allowed_mint;
+            allowed_mint.arbitrage_state = ctx.accounts.arbitrage_state.key();
+            allowed_mint.mint = mint;
+            allowed_mint.bump = ctx.bumps.allowed_mint;
+
+            emit!(MintAllowlistUpdated {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                mint,
+                allowed: true,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Mint allowlisted: {}", mint);
+            Ok(())
+        }
+
+        pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+            let mint = ctx.accounts.allowed_mint.mint;
+
+            emit!(MintAllowlistUpdated {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                mint,
+                allowed: false,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Mint removed from allowlist: {}", mint);
+            Ok(())
+        }
+
+        pub fn add_allowed_pool(ctx: Context<AddAllowedPool>, pool: Pubkey) -> Result<()> {
+            let allowed_pool = &mut ctx.accounts.allowed_pool;
+            allowed_pool.arbitrage_state = ctx.accounts.arbitrage_state.key();
+            allowed_pool.pool = pool;
+            allowed_pool.bump = ctx.bumps.allowed_pool;
+
+            emit!(PoolAllowlistUpdated {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                pool,
+                allowed: true,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Pool allowlisted: {}", pool);
+            Ok(())
+        }
+
+        pub fn remove_allowed_pool(ctx: Context<RemoveAllowedPool>) -> Result<()> {
+            let pool = ctx.accounts.allowed_pool.pool;
+
+            emit!(PoolAllowlistUpdated {
+                arbitrage_state: ctx.accounts.arbitrage_state.key(),
+                pool,
+                allowed: false,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            msg!("Pool removed from allowlist: {}", pool);
+            Ok(())
+        }
+
+        pub fn withdraw_profits(
+            ctx: Context<WithdrawProfits>,
+            amount: u64,
+        ) -> Result<()> {
+            require!(amount > 0, ArbitrageError::InvalidAmount);
+            require!(ctx.accounts.profit_vault.amount >= amount, ArbitrageError::InsufficientBalance);
+
+            msg!("Withdraw profits called by: {} for amount: {}", ctx.accounts.authority.key(), amount);
+
+            let arbitrage_state_key = ctx.accounts.arbitrage_state.key();
+            let vault_authority_seeds: &[&[u8]] = &[
+                b"vault_authority",
+                arbitrage_state_key.as_ref(),
+                &[ctx.bumps.vault_authority],
+            ];
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.profit_vault.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.destination.to_account_info(),
+                        authority: ctx.accounts.vault_authority.to_account_info(),
+                    },
+                    &[vault_authority_seeds],
+                ),
+                amount,
+                ctx.accounts.mint.decimals,
+            )?;
+
+            emit!(ProfitsWithdrawn {
+                authority: ctx.accounts.authority.key(),
+                mint: ctx.accounts.mint.key(),
+                destination: ctx.accounts.destination.key(),
+                amount,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+
+            Ok(())
+        }
     }
 }
+pub use program_entrypoint::*;
 
 // Account validation structs
 #[derive(Accounts)]
+#[instruction(state_id: Pubkey)]
 pub struct InitializeArbitrageState<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         init,
-        seeds = [b"arbitrage_state", authority.key().as_ref()],
+        seeds = [b"arbitrage_state", state_id.as_ref()],
         bump,
         payer = authority,
         space = ArbitrageState::LEN,
@@ -411,67 +1181,160 @@ pub struct InitializeArbitrageState<'info> {
 }
 
 #[derive(Accounts)]
-pub struct FlashArbitrage<'info> {
+pub struct MigrateState<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    /// CHECK: Written by an older layout that no longer deserializes; the handler checks the
+    /// discriminator, authority and PDA address before rewriting it
+    #[account(mut, owner = crate::ID)]
+    pub arbitrage_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeProfitVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [b"arbitrage_state", user.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
     )]
     pub arbitrage_state: Account<'info, ArbitrageState>,
 
-    // DEX program accounts (for future CPI calls)
-    /// CHECK: Whirlpool account for Orca swaps
-    #[account(mut)]
-    pub whirlpool: UncheckedAccount<'info>,
-    
-    /// CHECK: Raydium AMM ID
-    #[account(mut)]
-    pub amm_id: UncheckedAccount<'info>,
+    /// CHECK: PDA that owns every profit vault of this state; holds no data
+    #[account(seeds = [b"vault_authority", arbitrage_state.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
 
-    // Programs
-    pub token_program: Program<'info, Token>,
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        seeds = [b"profit_vault", arbitrage_state.key().as_ref(), mint.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program,
+    )]
+    pub profit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-// 🌊 NEW! Orca Swap Account Validation
 #[derive(Accounts)]
-pub struct OrcaSwap<'info> {
+pub struct InitializeMintStats<'info> {
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
-        mut,
-        seeds = [b"arbitrage_state", user.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
     )]
     pub arbitrage_state: Account<'info, ArbitrageState>,
 
-    // Orca Whirlpool accounts
-    /// CHECK: Whirlpool account validated by Orca program
-    #[account(mut)]
-    pub whirlpool: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    /// CHECK: Token owner account A validated by Orca
-    #[account(mut)]
-    pub token_owner_account_a: UncheckedAccount<'info>,
+    #[account(
+        init,
+        seeds = [b"mint_stats", arbitrage_state.key().as_ref(), mint.key().as_ref()],
+        bump,
+        payer = authority,
+        space = MintStats::LEN,
+    )]
+    pub mint_stats: Account<'info, MintStats>,
 
-    /// CHECK: Token vault A validated by Orca
-    #[account(mut)]
-    pub token_vault_a: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: Token owner account B validated by Orca
+#[derive(Accounts)]
+pub struct FlashArbitrage<'info> {
     #[account(mut)]
-    pub token_owner_account_b: UncheckedAccount<'info>,
+    pub user: Signer<'info>,
 
-    /// CHECK: Token vault B validated by Orca
-    #[account(mut)]
-    pub token_vault_b: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-    /// CHECK: Tick array 0 validated by Orca
-    #[account(mut)]
+    /// User token account for the first route's input mint; profit is measured from its balance delta
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ArbitrageError::AccountValidationFailed,
+        token::mint = input_mint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Input mint, read for Token-2022 transfer fees
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    /// Program-owned vault that receives the realized profit in the input mint
+    #[account(
+        mut,
+        seeds = [b"profit_vault", arbitrage_state.key().as_ref(), input_mint.key().as_ref()],
+        bump,
+    )]
+    pub profit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Stats for the input mint
+    #[account(mut, constraint = mint_stats.is_for(&arbitrage_state.key(), &input_mint.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats: Account<'info, MintStats>,
+
+    // Per-hop DEX accounts are passed via remaining_accounts (see `SwapRoute::hop_account_count`)
+
+    // Programs
+    /// Token program of the input mint, used to sweep profit
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Passed to Raydium AMM v4 / CLMM, Phoenix and OpenBook hops, which only support SPL Token
+    pub spl_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// 🌊 NEW! Orca Swap Account Validation
+#[derive(Accounts)]
+pub struct OrcaSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    // Orca Whirlpool accounts
+    /// CHECK: Whirlpool account validated by Orca program
+    #[account(mut)]
+    pub whirlpool: UncheckedAccount<'info>,
+
+    /// CHECK: Token owner account A validated by Orca
+    #[account(mut)]
+    pub token_owner_account_a: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault A validated by Orca
+    #[account(mut)]
+    pub token_vault_a: UncheckedAccount<'info>,
+
+    /// CHECK: Token owner account B validated by Orca
+    #[account(mut)]
+    pub token_owner_account_b: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault B validated by Orca
+    #[account(mut)]
+    pub token_vault_b: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array 0 validated by Orca
+    #[account(mut)]
     pub tick_array_0: UncheckedAccount<'info>,
 
     /// CHECK: Tick array 1 validated by Orca
@@ -485,7 +1348,28 @@ pub struct OrcaSwap<'info> {
     /// CHECK: Oracle account validated by Orca
     pub oracle: UncheckedAccount<'info>,
 
+    // Allowlist entries
+    /// Allowlist entry for the whirlpool
+    #[account(constraint = allowed_pool.allows(&arbitrage_state.key(), &whirlpool.key()) @ ArbitrageError::PoolNotAllowed)]
+    pub allowed_pool: Account<'info, AllowedPool>,
+
+    /// Allowlist entry for the whirlpool's mint A, checked in validate_accounts
+    pub allowed_mint_a: Account<'info, AllowedMint>,
+
+    /// Allowlist entry for the whirlpool's mint B, checked in validate_accounts
+    pub allowed_mint_b: Account<'info, AllowedMint>,
+
+    // Per-mint stats
+    /// Stats for the whirlpool's mint A, checked in validate_accounts
+    #[account(mut)]
+    pub mint_stats_a: Account<'info, MintStats>,
+
+    /// Stats for the whirlpool's mint B, checked in validate_accounts
+    #[account(mut)]
+    pub mint_stats_b: Account<'info, MintStats>,
+
     // Programs
+    /// Whirlpool `swap` (v1) only supports SPL Token; Token-2022 pools go through swap_v2
     pub token_program: Program<'info, Token>,
     
     /// CHECK: Orca Whirlpool program
@@ -493,6 +1377,334 @@ pub struct OrcaSwap<'info> {
     pub whirlpool_program: UncheckedAccount<'info>,
 }
 
+#[derive(Accounts)]
+pub struct OrcaSwapV2<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    // Orca Whirlpool accounts
+    /// CHECK: Whirlpool account validated by Orca program
+    #[account(mut)]
+    pub whirlpool: UncheckedAccount<'info>,
+
+    /// CHECK: Mint A checked against the whirlpool in validate_accounts
+    pub token_mint_a: UncheckedAccount<'info>,
+
+    /// CHECK: Mint B checked against the whirlpool in validate_accounts
+    pub token_mint_b: UncheckedAccount<'info>,
+
+    /// CHECK: Token owner account A validated by Orca
+    #[account(mut)]
+    pub token_owner_account_a: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault A validated by Orca
+    #[account(mut)]
+    pub token_vault_a: UncheckedAccount<'info>,
+
+    /// CHECK: Token owner account B validated by Orca
+    #[account(mut)]
+    pub token_owner_account_b: UncheckedAccount<'info>,
+
+    /// CHECK: Token vault B validated by Orca
+    #[account(mut)]
+    pub token_vault_b: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array 0 validated by Orca
+    #[account(mut)]
+    pub tick_array_0: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array 1 validated by Orca
+    #[account(mut)]
+    pub tick_array_1: UncheckedAccount<'info>,
+
+    /// CHECK: Tick array 2 validated by Orca
+    #[account(mut)]
+    pub tick_array_2: UncheckedAccount<'info>,
+
+    /// CHECK: Oracle account validated by Orca
+    #[account(mut)]
+    pub oracle: UncheckedAccount<'info>,
+
+    // Allowlist entries
+    /// Allowlist entry for the whirlpool
+    #[account(constraint = allowed_pool.allows(&arbitrage_state.key(), &whirlpool.key()) @ ArbitrageError::PoolNotAllowed)]
+    pub allowed_pool: Account<'info, AllowedPool>,
+
+    /// Allowlist entry for mint A
+    #[account(constraint = allowed_mint_a.allows(&arbitrage_state.key(), &token_mint_a.key()) @ ArbitrageError::MintNotAllowed)]
+    pub allowed_mint_a: Account<'info, AllowedMint>,
+
+    /// Allowlist entry for mint B
+    #[account(constraint = allowed_mint_b.allows(&arbitrage_state.key(), &token_mint_b.key()) @ ArbitrageError::MintNotAllowed)]
+    pub allowed_mint_b: Account<'info, AllowedMint>,
+
+    // Per-mint stats
+    /// Stats for mint A
+    #[account(mut, constraint = mint_stats_a.is_for(&arbitrage_state.key(), &token_mint_a.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats_a: Account<'info, MintStats>,
+
+    /// Stats for mint B
+    #[account(mut, constraint = mint_stats_b.is_for(&arbitrage_state.key(), &token_mint_b.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats_b: Account<'info, MintStats>,
+
+    // Programs
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+
+    /// CHECK: SPL Memo program
+    #[account(address = MEMO_PROGRAM_ID)]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// CHECK: Orca Whirlpool program
+    #[account(address = WHIRLPOOL_PROGRAM_ID)]
+    pub whirlpool_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OrcaTwoHopSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    // Orca Whirlpool accounts
+    /// CHECK: First whirlpool validated by Orca program
+    #[account(mut)]
+    pub whirlpool_one: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool validated by Orca program
+    #[account(mut)]
+    pub whirlpool_two: UncheckedAccount<'info>,
+
+    /// CHECK: Input mint checked against the whirlpools in validate_accounts
+    pub token_mint_input: UncheckedAccount<'info>,
+
+    /// CHECK: Intermediate mint checked against the whirlpools in validate_accounts
+    pub token_mint_intermediate: UncheckedAccount<'info>,
+
+    /// CHECK: Output mint checked against the whirlpools in validate_accounts
+    pub token_mint_output: UncheckedAccount<'info>,
+
+    /// CHECK: User input token account validated by Orca
+    #[account(mut)]
+    pub token_owner_account_input: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool input vault validated by Orca
+    #[account(mut)]
+    pub token_vault_one_input: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool intermediate vault validated by Orca
+    #[account(mut)]
+    pub token_vault_one_intermediate: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool intermediate vault validated by Orca
+    #[account(mut)]
+    pub token_vault_two_intermediate: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool output vault validated by Orca
+    #[account(mut)]
+    pub token_vault_two_output: UncheckedAccount<'info>,
+
+    /// CHECK: User output token account validated by Orca
+    #[account(mut)]
+    pub token_owner_account_output: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool tick array 0 validated by Orca
+    #[account(mut)]
+    pub tick_array_one_0: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool tick array 1 validated by Orca
+    #[account(mut)]
+    pub tick_array_one_1: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool tick array 2 validated by Orca
+    #[account(mut)]
+    pub tick_array_one_2: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool tick array 0 validated by Orca
+    #[account(mut)]
+    pub tick_array_two_0: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool tick array 1 validated by Orca
+    #[account(mut)]
+    pub tick_array_two_1: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool tick array 2 validated by Orca
+    #[account(mut)]
+    pub tick_array_two_2: UncheckedAccount<'info>,
+
+    /// CHECK: First whirlpool oracle validated by Orca
+    #[account(mut)]
+    pub oracle_one: UncheckedAccount<'info>,
+
+    /// CHECK: Second whirlpool oracle validated by Orca
+    #[account(mut)]
+    pub oracle_two: UncheckedAccount<'info>,
+
+    // Allowlist entries
+    /// Allowlist entry for the first whirlpool
+    #[account(constraint = allowed_pool_one.allows(&arbitrage_state.key(), &whirlpool_one.key()) @ ArbitrageError::PoolNotAllowed)]
+    pub allowed_pool_one: Account<'info, AllowedPool>,
+
+    /// Allowlist entry for the second whirlpool
+    #[account(constraint = allowed_pool_two.allows(&arbitrage_state.key(), &whirlpool_two.key()) @ ArbitrageError::PoolNotAllowed)]
+    pub allowed_pool_two: Account<'info, AllowedPool>,
+
+    /// Allowlist entry for the input mint
+    #[account(constraint = allowed_mint_input.allows(&arbitrage_state.key(), &token_mint_input.key()) @ ArbitrageError::MintNotAllowed)]
+    pub allowed_mint_input: Account<'info, AllowedMint>,
+
+    /// Allowlist entry for the intermediate mint
+    #[account(constraint = allowed_mint_intermediate.allows(&arbitrage_state.key(), &token_mint_intermediate.key()) @ ArbitrageError::MintNotAllowed)]
+    pub allowed_mint_intermediate: Account<'info, AllowedMint>,
+
+    /// Allowlist entry for the output mint
+    #[account(constraint = allowed_mint_output.allows(&arbitrage_state.key(), &token_mint_output.key()) @ ArbitrageError::MintNotAllowed)]
+    pub allowed_mint_output: Account<'info, AllowedMint>,
+
+    // Per-mint stats
+    /// Stats for the input mint
+    #[account(mut, constraint = mint_stats_input.is_for(&arbitrage_state.key(), &token_mint_input.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats_input: Account<'info, MintStats>,
+
+    /// Stats for the output mint
+    #[account(mut, constraint = mint_stats_output.is_for(&arbitrage_state.key(), &token_mint_output.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats_output: Account<'info, MintStats>,
+
+    // Programs
+    pub token_program_input: Interface<'info, TokenInterface>,
+    pub token_program_intermediate: Interface<'info, TokenInterface>,
+    pub token_program_output: Interface<'info, TokenInterface>,
+
+    /// CHECK: SPL Memo program
+    #[account(address = MEMO_PROGRAM_ID)]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// CHECK: Orca Whirlpool program
+    #[account(address = WHIRLPOOL_PROGRAM_ID)]
+    pub whirlpool_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RaydiumSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    // Raydium AMM v4 accounts
+    /// CHECK: AMM account validated by Raydium
+    #[account(mut)]
+    pub amm: UncheckedAccount<'info>,
+
+    /// CHECK: AMM authority PDA validated by Raydium
+    pub amm_authority: UncheckedAccount<'info>,
+
+    /// CHECK: AMM open orders validated by Raydium
+    #[account(mut)]
+    pub amm_open_orders: UncheckedAccount<'info>,
+
+    /// CHECK: AMM target orders validated by Raydium
+    #[account(mut)]
+    pub amm_target_orders: UncheckedAccount<'info>,
+
+    /// CHECK: Pool coin vault validated by Raydium
+    #[account(mut)]
+    pub pool_coin_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Pool pc vault validated by Raydium
+    #[account(mut)]
+    pub pool_pc_token_account: UncheckedAccount<'info>,
+
+    // Serum / OpenBook market accounts
+    /// CHECK: Serum / OpenBook program validated by Raydium
+    pub serum_program: UncheckedAccount<'info>,
+
+    /// CHECK: Market account validated by Raydium
+    #[account(mut)]
+    pub serum_market: UncheckedAccount<'info>,
+
+    /// CHECK: Market bids validated by Raydium
+    #[account(mut)]
+    pub serum_bids: UncheckedAccount<'info>,
+
+    /// CHECK: Market asks validated by Raydium
+    #[account(mut)]
+    pub serum_asks: UncheckedAccount<'info>,
+
+    /// CHECK: Market event queue validated by Raydium
+    #[account(mut)]
+    pub serum_event_queue: UncheckedAccount<'info>,
+
+    /// CHECK: Market coin vault validated by Raydium
+    #[account(mut)]
+    pub serum_coin_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Market pc vault validated by Raydium
+    #[account(mut)]
+    pub serum_pc_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Market vault signer validated by Raydium
+    pub serum_vault_signer: UncheckedAccount<'info>,
+
+    // User token accounts
+    /// CHECK: User source token account validated by Raydium
+    #[account(mut)]
+    pub user_source_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: User destination token account validated by Raydium
+    #[account(mut)]
+    pub user_destination_token_account: UncheckedAccount<'info>,
+
+    // Allowlist entries
+    /// Allowlist entry for the AMM
+    #[account(constraint = allowed_pool.allows(&arbitrage_state.key(), &amm.key()) @ ArbitrageError::PoolNotAllowed)]
+    pub allowed_pool: Account<'info, AllowedPool>,
+
+    /// Allowlist entry for the source token account's mint, checked in validate_accounts
+    pub allowed_source_mint: Account<'info, AllowedMint>,
+
+    /// Allowlist entry for the destination token account's mint, checked in validate_accounts
+    pub allowed_destination_mint: Account<'info, AllowedMint>,
+
+    // Per-mint stats
+    /// Stats for the source token account's mint, checked in validate_accounts
+    #[account(mut)]
+    pub mint_stats_source: Account<'info, MintStats>,
+
+    /// Stats for the destination token account's mint, checked in validate_accounts
+    #[account(mut)]
+    pub mint_stats_destination: Account<'info, MintStats>,
+
+    // Programs
+    /// AMM v4 pools only hold SPL Token mints
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Raydium AMM v4 program
+    #[account(address = RAYDIUM_AMM_PROGRAM_ID)]
+    pub raydium_amm_program: UncheckedAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct FlashLoanArbitrage<'info> {
     #[account(mut)]
@@ -500,46 +1712,76 @@ pub struct FlashLoanArbitrage<'info> {
 
     #[account(
         mut,
-        seeds = [b"arbitrage_state", user.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
+        constraint = arbitrage_state.is_trader(&user.key()) @ ArbitrageError::Unauthorized,
     )]
     pub arbitrage_state: Account<'info, ArbitrageState>,
 
-    // Flash loan accounts (placeholders for future integration)
-    /// CHECK: Solend market account
-    pub lending_market: UncheckedAccount<'info>,
-    /// CHECK: Reserve account for flash loan
+    /// User token account for the borrowed mint; profit is measured from its balance delta
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ArbitrageError::AccountValidationFailed,
+        token::mint = input_mint,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Borrowed mint, read for Token-2022 transfer fees
+    pub input_mint: InterfaceAccount<'info, Mint>,
+
+    /// Program-owned vault that receives the realized profit in the input mint
+    #[account(
+        mut,
+        seeds = [b"profit_vault", arbitrage_state.key().as_ref(), input_mint.key().as_ref()],
+        bump,
+    )]
+    pub profit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Stats for the borrowed mint
+    #[account(mut, constraint = mint_stats.is_for(&arbitrage_state.key(), &input_mint.key()) @ ArbitrageError::AccountValidationFailed)]
+    pub mint_stats: Account<'info, MintStats>,
+
+    // Solend flash loan accounts
+    /// CHECK: Reserve the surrounding FlashBorrow / FlashRepay draw from, matched against both;
+    /// its Solend ownership is checked when its fee config is read
     pub reserve: UncheckedAccount<'info>,
-    /// CHECK: Reserve liquidity supply
-    pub reserve_liquidity_supply: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    // Per-hop DEX accounts are passed via remaining_accounts (see `SwapRoute::hop_account_count`)
 
     // Programs
-    pub token_program: Program<'info, Token>,
+    /// Token program of the input mint, used to sweep profit
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Passed to Raydium AMM v4 / CLMM, Phoenix and OpenBook hops, which only support SPL Token
+    pub spl_token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct PauseBot<'info> {
+    /// The state authority or its guardian
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"arbitrage_state", authority.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
-        has_one = authority @ ArbitrageError::Unauthorized,
+        constraint = arbitrage_state.can_pause(&authority.key()) @ ArbitrageError::Unauthorized,
     )]
     pub arbitrage_state: Account<'info, ArbitrageState>,
 }
 
 #[derive(Accounts)]
-pub struct ResumeBot<'info> {
+pub struct SetGuardian<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"arbitrage_state", authority.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
         has_one = authority @ ArbitrageError::Unauthorized,
     )]
@@ -547,13 +1789,13 @@ pub struct ResumeBot<'info> {
 }
 
 #[derive(Accounts)]
-pub struct UpdateBotConfig<'info> {
+pub struct ResumeBot<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"arbitrage_state", authority.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
         has_one = authority @ ArbitrageError::Unauthorized,
     )]
@@ -561,283 +1803,2985 @@ pub struct UpdateBotConfig<'info> {
 }
 
 #[derive(Accounts)]
-pub struct WithdrawProfits<'info> {
+pub struct UpdateBotConfig<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"arbitrage_state", authority.key().as_ref()],
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
         bump = arbitrage_state.bump,
         has_one = authority @ ArbitrageError::Unauthorized,
     )]
     pub arbitrage_state: Account<'info, ArbitrageState>,
 }
 
-// Data structures
-#[account]
-pub struct ArbitrageState {
-    pub authority: Pubkey,
-    pub is_paused: bool,
-    pub min_execution_interval: i64,
-    pub last_execution_time: i64,
-    pub total_trades: u64,
-    pub total_profit: u64,
-    pub bump: u8,
-}
-
-impl ArbitrageState {
-    pub const LEN: usize = 8 + 32 + 1 + 8 + 8 + 8 + 8 + 1;
-}
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct SwapRoute {
-    pub dex_id: DexId,
-    pub input_mint: Pubkey,
-    pub output_mint: Pubkey,
-    pub amount_in: u64,
-    pub min_amount_out: u64,
-}
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub enum DexId {
-    Orca,
-    Raydium,
-    Jupiter,
-}
+    #[account(
+        init,
+        seeds = [
+            b"config_change",
+            arbitrage_state.key().as_ref(),
+            arbitrage_state.next_config_change_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = PendingConfigChange::LEN,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
 
-// Events
-#[event]
-pub struct ArbitrageStateInitialized {
-    pub authority: Pubkey,
-    pub min_execution_interval: i64,
-    pub timestamp: i64,
+    pub system_program: Program<'info, System>,
 }
 
-#[event]
-pub struct ArbitrageExecuted {
-    pub user: Pubkey,
-    pub profit: u64,
-    pub routes: u8,
-    pub timestamp: i64,
-}
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-#[event]
-pub struct FlashLoanArbitrageExecuted {
-    pub user: Pubkey,
-    pub flash_loan_amount: u64,
-    pub profit: u64,
-    pub routes: u8,
-    pub timestamp: i64,
-}
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-// 🌊 NEW! Orca Swap Event
-#[event]
-pub struct OrcaSwapExecuted {
-    pub user: Pubkey,
-    pub whirlpool: Pubkey,
-    pub amount: u64,
-    pub other_amount_threshold: u64,
-    pub a_to_b: bool,
-    pub timestamp: i64,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"config_change",
+            arbitrage_state.key().as_ref(),
+            pending_config_change.change_id.to_le_bytes().as_ref(),
+        ],
+        bump = pending_config_change.bump,
+        has_one = arbitrage_state @ ArbitrageError::AccountValidationFailed,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
 }
 
-#[event]
-pub struct BotPaused {
-    pub authority: Pubkey,
-    pub timestamp: i64,
-}
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-#[event]
-pub struct BotResumed {
-    pub authority: Pubkey,
-    pub timestamp: i64,
-}
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-#[event]
-pub struct BotConfigUpdated {
-    pub authority: Pubkey,
-    pub new_min_execution_interval: Option<i64>,
-    pub timestamp: i64,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"config_change",
+            arbitrage_state.key().as_ref(),
+            pending_config_change.change_id.to_le_bytes().as_ref(),
+        ],
+        bump = pending_config_change.bump,
+        has_one = arbitrage_state @ ArbitrageError::AccountValidationFailed,
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
 }
 
-#[event]
-pub struct ProfitsWithdrawn {
-    pub authority: Pubkey,
-    pub amount: u64,
-    pub timestamp: i64,
-}
+#[derive(Accounts)]
+pub struct ManageOperators<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-#[error_code]
-pub enum ArbitrageError {
-    #[msg("Invalid amount provided")]
-    InvalidAmount,
-    #[msg("No swap routes provided")]
-    EmptyRoutes,
-    #[msg("Too many swap hops (max 4)")]
-    TooManyHops,
-    #[msg("Bot is currently paused")]
-    BotPaused,
-    #[msg("Execution too frequent - rate limited")]
-    ExecutionTooFrequent,
-    #[msg("Insufficient profit after execution")]
-    InsufficientProfit,
-    #[msg("Insufficient balance for operation")]
-    InsufficientBalance,
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    #[msg("Slippage tolerance exceeded")]
-    SlippageExceeded,
-    #[msg("Pool liquidity insufficient")]
-    InsufficientLiquidity,
-    #[msg("Invalid swap pair - same input and output mint")]
-    InvalidSwapPair,
-    #[msg("Account validation failed")]
-    AccountValidationFailed,
-    #[msg("Arithmetic overflow or underflow")]
-    ArithmeticError,
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 }
 
-// 🌊 Orca Whirlpool CPI module - simplified version for direct invoke
-pub mod whirlpool_swap {
-    use super::*;
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
 
-    #[derive(AnchorSerialize, AnchorDeserialize)]
-    pub struct SwapInstruction {
-        pub amount: u64,
-        pub other_amount_threshold: u64,
-        pub sqrt_price_limit: u128,
-        pub amount_specified_is_input: bool,
-        pub a_to_b: bool,
-    }
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 }
 
-// 🏦 Solend Flash Loan Integration
-pub fn create_solend_flash_loan_instruction(
-    user: &Pubkey,
-    reserve: &Pubkey,
-    reserve_liquidity_supply: &Pubkey,
-    amount: u64,
-) -> Result<Instruction> {
-    let flash_loan_ix = Instruction {
-        program_id: SOLEND_PROGRAM_ID,
-        accounts: vec![
-            AccountMeta::new(*user, true),
-            AccountMeta::new(*reserve, false),
-            AccountMeta::new(*reserve_liquidity_supply, false),
-            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-        ],
-        data: {
-            let mut data = vec![0x12, 0x34, 0x56, 0x78]; // Flash loan discriminator (placeholder)
-            data.append(&mut amount.to_le_bytes().to_vec());
-            data
-        },
-    };
-    Ok(flash_loan_ix)
-}
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_authority: Signer<'info>,
 
-pub fn create_solend_flash_loan_repay_instruction(
-    user: &Pubkey,
-    reserve: &Pubkey,
-    amount: u64,
-) -> Result<Instruction> {
-    let repay_ix = Instruction {
-        program_id: SOLEND_PROGRAM_ID,
-        accounts: vec![
-            AccountMeta::new(*user, true),
-            AccountMeta::new(*reserve, false),
-            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-        ],
-        data: {
-            let mut data = vec![0x87, 0x65, 0x43, 0x21]; // Repay discriminator (placeholder)
-            data.append(&mut amount.to_le_bytes().to_vec());
-            data
-        },
-    };
-    Ok(repay_ix)
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        constraint = arbitrage_state.pending_authority == Some(pending_authority.key()) @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 }
 
-pub fn calculate_flash_loan_fee(amount: u64) -> u64 {
-    // Solend typically charges 0.09% flash loan fee
-    amount * 9 / 10000
-}
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct AddAllowedMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-pub fn execute_orca_swap_with_flash_loan(
-    user: &Pubkey,
-    amount_in: u64,
-    min_amount_out: u64,
-) -> Result<()> {
-    msg!("🌊 Executing Orca swap with flash loan funds");
-    
-    // Build Orca swap instruction with flash loan funds
-    let swap_instruction = whirlpool_swap::SwapInstruction {
-        amount: amount_in,
-        other_amount_threshold: min_amount_out,
-        sqrt_price_limit: u128::MAX, // No price limit for flash loan arbitrage
-        amount_specified_is_input: true,
-        a_to_b: true,
-    };
+    #[account(
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-    let _swap_ix = Instruction {
-        program_id: WHIRLPOOL_PROGRAM_ID,
-        accounts: vec![
-            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new_readonly(*user, true),
-            // Additional Orca accounts would be passed here
-        ],
-        data: {
-            let mut data = vec![0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
-            data.append(&mut swap_instruction.try_to_vec().unwrap());
-            data
-        },
-    };
+    #[account(
+        init,
+        seeds = [b"allowed_mint", arbitrage_state.key().as_ref(), mint.as_ref()],
+        bump,
+        payer = authority,
+        space = AllowedMint::LEN,
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
 
-    msg!("📞 Calling Orca with flash loan amount: {}", amount_in);
-    // Real invoke would happen here with proper accounts
-    Ok(())
+    pub system_program: Program<'info, System>,
 }
 
-pub fn execute_raydium_swap_with_flash_loan(
-    user: &Pubkey,
-    amount_in: u64,
-    min_amount_out: u64,
-) -> Result<()> {
-    msg!("⚡ Executing Raydium swap with flash loan funds");
-    
-    let _swap_ix = Instruction {
-        program_id: RAYDIUM_AMM_PROGRAM_ID,
-        accounts: vec![
-            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-            AccountMeta::new_readonly(*user, true),
-            // Raydium AMM accounts would be here
-        ],
-        data: {
-            let mut data = vec![0x09]; // Raydium swap discriminator
-            data.append(&mut amount_in.to_le_bytes().to_vec());
-            data.append(&mut min_amount_out.to_le_bytes().to_vec());
-            data
-        },
-    };
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-    msg!("📞 Calling Raydium with flash loan amount: {}", amount_in);
-    // Real invoke would happen here
-    Ok(())
-}
+    #[account(
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
 
-pub fn execute_jupiter_swap_with_flash_loan(
-    user: &Pubkey,
-    amount_in: u64,
-    _min_amount_out: u64,
-) -> Result<()> {
-    msg!("🪐 Executing Jupiter swap with flash loan funds");
-    
-    msg!("📞 Calling Jupiter with flash loan amount: {}", amount_in);
-    msg!("👤 User: {}", user);
-    // Jupiter integration would happen here
-    Ok(())
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"allowed_mint", arbitrage_state.key().as_ref(), allowed_mint.mint.as_ref()],
+        bump = allowed_mint.bump,
+        has_one = arbitrage_state @ ArbitrageError::AccountValidationFailed,
+    )]
+    pub allowed_mint: Account<'info, AllowedMint>,
 }
 
-// Convenience functions for OrcaSwap
+#[derive(Accounts)]
+#[instruction(pool: Pubkey)]
+pub struct AddAllowedPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    #[account(
+        init,
+        seeds = [b"allowed_pool", arbitrage_state.key().as_ref(), pool.as_ref()],
+        bump,
+        payer = authority,
+        space = AllowedPool::LEN,
+    )]
+    pub allowed_pool: Account<'info, AllowedPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"allowed_pool", arbitrage_state.key().as_ref(), allowed_pool.pool.as_ref()],
+        bump = allowed_pool.bump,
+        has_one = arbitrage_state @ ArbitrageError::AccountValidationFailed,
+    )]
+    pub allowed_pool: Account<'info, AllowedPool>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProfits<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"arbitrage_state", arbitrage_state.state_id.as_ref()],
+        bump = arbitrage_state.bump,
+        has_one = authority @ ArbitrageError::Unauthorized,
+    )]
+    pub arbitrage_state: Account<'info, ArbitrageState>,
+
+    /// CHECK: PDA that owns every profit vault of this state; signs the withdrawal
+    #[account(seeds = [b"vault_authority", arbitrage_state.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"profit_vault", arbitrage_state.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub profit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority-chosen destination for the withdrawn profit
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Data structures
+#[account]
+pub struct ArbitrageState {
+    /// Layout version; always the first byte after the discriminator so `migrate_state` can read it
+    pub version: u8,
+    /// Stable PDA seed, so the account survives authority rotation. Accounts migrated from the
+    /// unversioned layout keep their original authority key here.
+    pub state_id: Pubkey,
+    pub authority: Pubkey,
+    /// Proposed new authority; takes effect once it signs `accept_authority`
+    pub pending_authority: Option<Pubkey>,
+    /// Hot keys allowed to execute trades; only the authority can configure or withdraw
+    pub operators: Vec<Pubkey>,
+    /// Low-privilege key that may pause the bot but nothing else
+    pub guardian: Option<Pubkey>,
+    pub is_paused: bool,
+    pub min_execution_interval: i64,
+    pub last_execution_time: i64,
+    pub total_trades: u64,
+    pub total_profit: u64,
+    /// Delay in seconds before a queued config change can be executed
+    pub config_timelock: i64,
+    /// Maximum number of hops in a route
+    pub max_hops: u8,
+    /// Minimum realized profit in basis points of the route input
+    pub min_profit_bps: u16,
+    /// Per-mint caps on the input amount of any single leg; unlisted mints are uncapped
+    pub mint_notional_limits: Vec<MintNotionalLimit>,
+    /// Largest loss an opted-in (`land_failed_trade`) trade may realize and still land as a
+    /// failure; larger losses revert
+    pub max_loss_per_trade: u64,
+    /// Daily realized loss that trips the circuit breaker; 0 disables the check
+    pub max_daily_loss: u64,
+    /// Consecutive failed trades that trip the circuit breaker; 0 disables the check. Only
+    /// failures landed through `land_failed_trade` count; reverted trades leave no trace
+    pub max_consecutive_failures: u16,
+    /// UTC day (unix time / 86400) that `daily_pnl` accumulates for
+    pub pnl_day: i64,
+    pub daily_pnl: i64,
+    pub consecutive_failures: u16,
+    /// Id assigned to the next queued config change; part of its PDA seed
+    pub next_config_change_id: u64,
+    pub bump: u8,
+    /// Whether Jupiter legs may run; the aggregator picks pools the pool allowlist cannot see
+    pub allow_aggregator_legs: bool,
+    /// Zeroed space for future fields, so adding one does not need a realloc
+    pub reserved: [u8; ArbitrageState::RESERVED_SPACE],
+}
+
+impl ArbitrageState {
+    pub const MAX_OPERATORS: usize = 8;
+    pub const MAX_MINT_NOTIONAL_LIMITS: usize = 8;
+    /// Upper bound for the configurable hop cap
+    pub const MAX_ROUTE_HOPS: u8 = 8;
+    pub const DEFAULT_MAX_HOPS: u8 = 4;
+    pub const LEN: usize = 8 + 1 + 32 + 32 + (1 + 32) + (4 + 32 * Self::MAX_OPERATORS) + (1 + 32) + 1 + 8 + 8 + 8 + 8 + 8
+        + 1 + 2 + (4 + MintNotionalLimit::LEN * Self::MAX_MINT_NOTIONAL_LIMITS)
+        + 8 + 8 + 2 + 8 + 8 + 2 + 8 + 1 + 1 + Self::RESERVED_SPACE;
+    pub const CURRENT_VERSION: u8 = 1;
+    pub const RESERVED_SPACE: usize = 127;
+    /// Size of the original unversioned layout: discriminator, authority, is_paused,
+    /// min_execution_interval, last_execution_time, total_trades, total_profit, bump
+    pub const LEGACY_LEN: usize = 8 + 32 + 1 + 8 + 8 + 8 + 8 + 1;
+
+    /// Timelock applied to queued config changes until the authority configures one
+    pub const DEFAULT_CONFIG_TIMELOCK: i64 = 24 * 60 * 60;
+    /// Upper bound on the timelock, so `queued_at + config_timelock` cannot overflow
+    pub const MAX_CONFIG_TIMELOCK: i64 = 30 * 24 * 60 * 60;
+
+    /// A state at the current version with every optional limit at its default
+    pub fn new(state_id: Pubkey, authority: Pubkey, min_execution_interval: i64, bump: u8) -> Self {
+        Self {
+            version: Self::CURRENT_VERSION,
+            state_id,
+            authority,
+            pending_authority: None,
+            operators: Vec::new(),
+            guardian: None,
+            is_paused: false,
+            min_execution_interval,
+            last_execution_time: 0,
+            total_trades: 0,
+            total_profit: 0,
+            config_timelock: Self::DEFAULT_CONFIG_TIMELOCK,
+            max_hops: Self::DEFAULT_MAX_HOPS,
+            min_profit_bps: 0,
+            mint_notional_limits: Vec::new(),
+            max_loss_per_trade: 0,
+            max_daily_loss: 0,
+            max_consecutive_failures: 0,
+            pnl_day: 0,
+            daily_pnl: 0,
+            consecutive_failures: 0,
+            next_config_change_id: 0,
+            bump,
+            allow_aggregator_legs: false,
+            reserved: [0; Self::RESERVED_SPACE],
+        }
+    }
+
+    /// Rebuild a state from the original unversioned layout, keeping its counters and
+    /// defaulting everything added since. The authority key becomes the stable state id,
+    /// which keeps the account at its existing address.
+    pub fn from_legacy(data: &[u8]) -> Result<Self> {
+        require!(data.len() == Self::LEGACY_LEN, ArbitrageError::UnsupportedStateVersion);
+        let read_i64 = |offset: usize| i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        let authority = Pubkey::new_from_array(data[8..40].try_into().unwrap());
+        let mut state = Self::new(authority, authority, read_i64(41), data[73]);
+        state.is_paused = data[40] != 0;
+        state.last_execution_time = read_i64(49);
+        state.total_trades = read_u64(57);
+        state.total_profit = read_u64(65);
+        Ok(state)
+    }
+
+    /// The authority and any registered operator may execute trades
+    pub fn is_trader(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.operators.contains(key)
+    }
+
+    /// The authority and the guardian, if set, may pause the bot
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.guardian == Some(*key)
+    }
+
+    pub fn notional_limit(&self, mint: &Pubkey) -> Option<u64> {
+        self.mint_notional_limits
+            .iter()
+            .find(|limit| limit.mint == *mint)
+            .map(|limit| limit.max_amount_in)
+    }
+
+    pub fn check_notional_limit(&self, mint: &Pubkey, amount_in: u64) -> Result<()> {
+        if let Some(max_amount_in) = self.notional_limit(mint) {
+            require!(amount_in <= max_amount_in, ArbitrageError::NotionalLimitExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn check_min_profit_bps(&self, profit: u64, input_amount: u64) -> Result<()> {
+        let required_profit = (input_amount as u128) * (self.min_profit_bps as u128) / 10_000;
+        require!(profit as u128 >= required_profit, ArbitrageError::ProfitBelowMinimumBps);
+        Ok(())
+    }
+
+    /// Count a landed trade and add any realized gain to the lifetime profit, whether or not
+    /// the trade met its `min_profit`, matching what `MintStats` records for it
+    pub fn record_trade_totals(&mut self, realized_pnl: i64) -> Result<()> {
+        self.total_trades = self.total_trades.checked_add(1).ok_or(ArbitrageError::ArithmeticError)?;
+        if realized_pnl > 0 {
+            self.total_profit = self
+                .total_profit
+                .checked_add(realized_pnl.unsigned_abs())
+                .ok_or(ArbitrageError::ArithmeticError)?;
+        }
+        Ok(())
+    }
+
+    /// Roll the daily PnL window, update the failure streak and pause the bot if either
+    /// configured circuit-breaker threshold is hit
+    pub fn record_trade_result(
+        &mut self,
+        realized_pnl: i64,
+        succeeded: bool,
+        current_time: i64,
+    ) -> Result<Option<CircuitBreakerReason>> {
+        let day = current_time.div_euclid(SECONDS_PER_DAY);
+        if day != self.pnl_day {
+            self.pnl_day = day;
+            self.daily_pnl = 0;
+        }
+        self.daily_pnl = self
+            .daily_pnl
+            .checked_add(realized_pnl)
+            .ok_or(ArbitrageError::ArithmeticError)?;
+        self.consecutive_failures = if succeeded {
+            0
+        } else {
+            self.consecutive_failures.saturating_add(1)
+        };
+
+        let daily_loss = self.daily_pnl.min(0).unsigned_abs();
+        let reason = if self.max_daily_loss > 0 && daily_loss >= self.max_daily_loss {
+            Some(CircuitBreakerReason::DailyLossLimit)
+        } else if self.max_consecutive_failures > 0
+            && self.consecutive_failures >= self.max_consecutive_failures
+        {
+            Some(CircuitBreakerReason::FailureStreak)
+        } else {
+            None
+        };
+        if reason.is_some() {
+            self.is_paused = true;
+        }
+        Ok(reason)
+    }
+
+    /// Whether `change` relaxes any limit compared to the current configuration
+    pub fn is_loosened_by(&self, change: &ConfigChange) -> bool {
+        change
+            .min_execution_interval
+            .is_some_and(|interval| interval < self.min_execution_interval)
+            || change
+                .config_timelock
+                .is_some_and(|timelock| timelock < self.config_timelock)
+            || change.max_hops.is_some_and(|max_hops| max_hops > self.max_hops)
+            || change
+                .min_profit_bps
+                .is_some_and(|bps| bps < self.min_profit_bps)
+            || change.mint_notional_limit.as_ref().is_some_and(|limit| {
+                self.notional_limit(&limit.mint)
+                    .is_some_and(|current| limit.max_amount_in > current)
+            })
+            || change
+                .max_loss_per_trade
+                .is_some_and(|max_loss| max_loss > self.max_loss_per_trade)
+            || change
+                .max_daily_loss
+                .is_some_and(|max_loss| loosens_threshold(self.max_daily_loss, max_loss))
+            || change
+                .max_consecutive_failures
+                .is_some_and(|max_failures| {
+                    loosens_threshold(self.max_consecutive_failures as u64, max_failures as u64)
+                })
+            || change
+                .allow_aggregator_legs
+                .is_some_and(|allow| allow && !self.allow_aggregator_legs)
+    }
+
+    pub fn apply_config_change(&mut self, change: &ConfigChange) -> Result<()> {
+        if let Some(interval) = change.min_execution_interval {
+            self.min_execution_interval = interval;
+        }
+        if let Some(timelock) = change.config_timelock {
+            self.config_timelock = timelock;
+        }
+        if let Some(max_hops) = change.max_hops {
+            self.max_hops = max_hops;
+        }
+        if let Some(bps) = change.min_profit_bps {
+            self.min_profit_bps = bps;
+        }
+        if let Some(max_loss) = change.max_loss_per_trade {
+            self.max_loss_per_trade = max_loss;
+        }
+        if let Some(max_loss) = change.max_daily_loss {
+            self.max_daily_loss = max_loss;
+        }
+        if let Some(max_failures) = change.max_consecutive_failures {
+            self.max_consecutive_failures = max_failures;
+        }
+        if let Some(allow) = change.allow_aggregator_legs {
+            self.allow_aggregator_legs = allow;
+        }
+        if let Some(limit) = &change.mint_notional_limit {
+            let existing = self
+                .mint_notional_limits
+                .iter()
+                .position(|current| current.mint == limit.mint);
+            match (existing, limit.max_amount_in) {
+                // A cap of u64::MAX is no cap; free the slot
+                (Some(index), u64::MAX) => {
+                    self.mint_notional_limits.swap_remove(index);
+                }
+                (Some(index), _) => self.mint_notional_limits[index] = limit.clone(),
+                (None, u64::MAX) => {}
+                (None, _) => {
+                    require!(
+                        self.mint_notional_limits.len() < Self::MAX_MINT_NOTIONAL_LIMITS,
+                        ArbitrageError::TooManyMintLimits
+                    );
+                    self.mint_notional_limits.push(limit.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A circuit-breaker threshold where 0 means disabled; raising it or disabling it loosens
+fn loosens_threshold(current: u64, new: u64) -> bool {
+    current != 0 && (new == 0 || new > current)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitBreakerReason {
+    DailyLossLimit,
+    FailureStreak,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintNotionalLimit {
+    pub mint: Pubkey,
+    pub max_amount_in: u64,
+}
+
+impl MintNotionalLimit {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Admin-configurable parameters; `None` leaves a value unchanged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ConfigChange {
+    pub min_execution_interval: Option<i64>,
+    pub config_timelock: Option<i64>,
+    pub max_hops: Option<u8>,
+    pub min_profit_bps: Option<u16>,
+    /// Sets the cap for one mint; `u64::MAX` removes it
+    pub mint_notional_limit: Option<MintNotionalLimit>,
+    pub max_loss_per_trade: Option<u64>,
+    pub max_daily_loss: Option<u64>,
+    pub max_consecutive_failures: Option<u16>,
+    /// Enabling Jupiter legs loosens the pool allowlist and needs the timelock
+    pub allow_aggregator_legs: Option<bool>,
+}
+
+impl ConfigChange {
+    pub const LEN: usize = (1 + 8) + (1 + 8) + (1 + 1) + (1 + 2) + (1 + MintNotionalLimit::LEN)
+        + (1 + 8) + (1 + 8) + (1 + 2) + (1 + 1);
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_execution_interval.is_some()
+                || self.config_timelock.is_some()
+                || self.max_hops.is_some()
+                || self.min_profit_bps.is_some()
+                || self.mint_notional_limit.is_some()
+                || self.max_loss_per_trade.is_some()
+                || self.max_daily_loss.is_some()
+                || self.max_consecutive_failures.is_some()
+                || self.allow_aggregator_legs.is_some(),
+            ArbitrageError::InvalidConfigChange
+        );
+        require!(
+            self.max_hops
+                .is_none_or(|max_hops| (1..=ArbitrageState::MAX_ROUTE_HOPS).contains(&max_hops)),
+            ArbitrageError::InvalidConfigChange
+        );
+        require!(
+            self.min_profit_bps.is_none_or(|bps| bps <= 10_000),
+            ArbitrageError::InvalidConfigChange
+        );
+        require!(
+            self.min_execution_interval.is_none_or(|interval| interval >= 0),
+            ArbitrageError::InvalidConfigChange
+        );
+        require!(
+            self.config_timelock
+                .is_none_or(|timelock| (0..=ArbitrageState::MAX_CONFIG_TIMELOCK).contains(&timelock)),
+            ArbitrageError::InvalidConfigChange
+        );
+        Ok(())
+    }
+}
+
+/// Per-asset trade accounting, so volume and PnL are never summed across mints
+#[account]
+pub struct MintStats {
+    pub arbitrage_state: Pubkey,
+    pub mint: Pubkey,
+    pub trades: u64,
+    pub volume_in: u64,
+    pub volume_out: u64,
+    pub realized_profit: u64,
+    pub realized_loss: u64,
+    pub bump: u8,
+}
+
+impl MintStats {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn is_for(&self, arbitrage_state: &Pubkey, mint: &Pubkey) -> bool {
+        self.arbitrage_state == *arbitrage_state && self.mint == *mint
+    }
+
+    pub fn record_trade(&mut self, volume_in: u64, volume_out: u64, realized_pnl: i64) -> Result<()> {
+        self.trades = self.trades.checked_add(1).ok_or(ArbitrageError::ArithmeticError)?;
+        self.volume_in = self.volume_in.checked_add(volume_in).ok_or(ArbitrageError::ArithmeticError)?;
+        self.volume_out = self.volume_out.checked_add(volume_out).ok_or(ArbitrageError::ArithmeticError)?;
+        if realized_pnl >= 0 {
+            self.realized_profit = self
+                .realized_profit
+                .checked_add(realized_pnl.unsigned_abs())
+                .ok_or(ArbitrageError::ArithmeticError)?;
+        } else {
+            self.realized_loss = self
+                .realized_loss
+                .checked_add(realized_pnl.unsigned_abs())
+                .ok_or(ArbitrageError::ArithmeticError)?;
+        }
+        Ok(())
+    }
+
+    /// A closed cycle sends `amount_in` out and gets it back plus the realized PnL
+    pub fn record_arbitrage(&mut self, amount_in: u64, realized_pnl: i64) -> Result<()> {
+        let amount_out = amount_in
+            .checked_add_signed(realized_pnl)
+            .ok_or(ArbitrageError::ArithmeticError)?;
+        self.record_trade(amount_in, amount_out, realized_pnl)
+    }
+}
+
+/// Allowlist entry permitting route legs to trade `mint`
+#[account]
+pub struct AllowedMint {
+    pub arbitrage_state: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowedMint {
+    pub const LEN: usize = 8 + 32 + 32 + 1;
+
+    pub fn allows(&self, arbitrage_state: &Pubkey, mint: &Pubkey) -> bool {
+        self.arbitrage_state == *arbitrage_state && self.mint == *mint
+    }
+}
+
+/// Allowlist entry permitting route legs to trade through `pool`
+#[account]
+pub struct AllowedPool {
+    pub arbitrage_state: Pubkey,
+    pub pool: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowedPool {
+    pub const LEN: usize = 8 + 32 + 32 + 1;
+
+    pub fn allows(&self, arbitrage_state: &Pubkey, pool: &Pubkey) -> bool {
+        self.arbitrage_state == *arbitrage_state && self.pool == *pool
+    }
+}
+
+/// A config change waiting out the state's timelock
+#[account]
+pub struct PendingConfigChange {
+    pub arbitrage_state: Pubkey,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub queued_at: i64,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+impl PendingConfigChange {
+    pub const LEN: usize = 8 + 32 + 8 + ConfigChange::LEN + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapRoute {
+    pub dex_id: DexId,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    /// Off-chain route plan, required for `DexId::Jupiter` legs
+    pub jupiter_route: Option<JupiterRoutePlan>,
+}
+
+/// How route hops after the first get their input amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteAmountMode {
+    /// Every hop swaps its own `SwapRoute::amount_in`
+    Fixed,
+    /// The first hop swaps its `amount_in`; each later hop swaps the output measured
+    /// from the previous hop's destination balance delta
+    ChainOutput,
+}
+
+/// Reject a route sequence before any CPI unless every hop swaps between two different
+/// mints, each hop starts in the mint the previous one ended in, and the last hop returns
+/// to the mint the first one started from. This only sees the declared mints;
+/// `validate_route_accounts` binds them to the hop accounts.
+pub fn validate_route_chain(routes: &[SwapRoute]) -> Result<()> {
+    let (first, last) = match (routes.first(), routes.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return err!(ArbitrageError::EmptyRoutes),
+    };
+
+    for route in routes {
+        require_keys_neq!(route.input_mint, route.output_mint, ArbitrageError::InvalidSwapPair);
+        route.check_route_plan()?;
+    }
+    for pair in routes.windows(2) {
+        require_keys_eq!(pair[0].output_mint, pair[1].input_mint, ArbitrageError::BrokenRouteChain);
+    }
+    require_keys_eq!(last.output_mint, first.input_mint, ArbitrageError::RouteNotClosed);
+    Ok(())
+}
+
+impl SwapRoute {
+    /// Number of `remaining_accounts` consumed by this hop (see the `DexId` account layout)
+    pub fn hop_account_count(&self) -> usize {
+        match (&self.dex_id, &self.jupiter_route) {
+            (DexId::Jupiter, Some(plan)) => self.dex_id.hop_account_count() + plan.account_count as usize,
+            _ => self.dex_id.hop_account_count(),
+        }
+    }
+
+    /// A Jupiter route plan belongs on Jupiter legs only
+    pub fn check_route_plan(&self) -> Result<()> {
+        if !matches!(self.dex_id, DexId::Jupiter) {
+            require!(self.jupiter_route.is_none(), ArbitrageError::UnexpectedRoutePlan);
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct JupiterRoutePlan {
+    /// `Some(id)` to call `shared_accounts_route`, `None` to call `route`
+    pub shared_accounts_id: Option<u8>,
+    /// Borsh-encoded `Vec<RoutePlanStep>` from the Jupiter swap-instructions API
+    pub route_plan: Vec<u8>,
+    /// Number of Jupiter instruction accounts following the common hop accounts
+    pub account_count: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum DexId {
+    Orca,
+    Raydium,
+    Jupiter,
+    RaydiumClmm,
+    RaydiumCpmm,
+    MeteoraDlmm,
+    Phoenix,
+    OpenBookV2,
+}
+
+/// Route execution account layout.
+///
+/// Each `SwapRoute` consumes `ROUTE_ALLOWLIST_ACCOUNT_COUNT + SwapRoute::hop_account_count()`
+/// accounts from `ctx.remaining_accounts`, in route order. The allowlist entries come first:
+///
+///   a. `AllowedPool` entry for the hop's pool (`DexId::pool_account_index`); unused for
+///      Jupiter legs, which instead need `ArbitrageState::allow_aggregator_legs`
+///   b. `AllowedMint` entry for `input_mint`
+///   c. `AllowedMint` entry for `output_mint`
+///
+/// Every hop then starts with the same three accounts:
+///
+///   0. DEX program
+///   1. User source token account (holds `input_mint`, writable)
+///   2. User destination token account (receives `output_mint`, writable)
+///
+/// followed by the DEX-specific accounts:
+///
+/// `DexId::Orca` (Whirlpool `swap_v2`):
+///   3. whirlpool (writable)
+///   4. token_mint_a
+///   5. token_mint_b
+///   6. token_vault_a (writable)
+///   7. token_vault_b (writable)
+///   8. tick_array_0 (writable)
+///   9. tick_array_1 (writable)
+///  10. tick_array_2 (writable)
+///  11. oracle (writable)
+///  12. token_program_a
+///  13. token_program_b
+///  14. memo_program
+///
+/// `DexId::Raydium` (AMM v4 `SwapBaseIn`):
+///   3. amm (writable)
+///   4. amm_authority
+///   5. amm_open_orders (writable)
+///   6. amm_target_orders (writable)
+///   7. pool_coin_token_account (writable)
+///   8. pool_pc_token_account (writable)
+///   9. serum_program
+///  10. serum_market (writable)
+///  11. serum_bids (writable)
+///  12. serum_asks (writable)
+///  13. serum_event_queue (writable)
+///  14. serum_coin_vault (writable)
+///  15. serum_pc_vault (writable)
+///  16. serum_vault_signer
+///
+/// `DexId::RaydiumClmm` (CLMM `swap_v2`):
+///   3. amm_config
+///   4. pool_state (writable)
+///   5. input_vault (writable)
+///   6. output_vault (writable)
+///   7. observation_state (writable)
+///   8. token_program_2022
+///   9. memo_program
+///  10. input_vault_mint
+///  11. output_vault_mint
+///  12. tick_array_bitmap_extension (writable)
+///  13. tick_array_0 (writable)
+///  14. tick_array_1 (writable)
+///  15. tick_array_2 (writable)
+///
+/// `DexId::RaydiumCpmm` (CP-Swap `swap_base_input`):
+///   3. authority
+///   4. amm_config
+///   5. pool_state (writable)
+///   6. input_vault (writable)
+///   7. output_vault (writable)
+///   8. input_token_program
+///   9. output_token_program
+///  10. input_token_mint
+///  11. output_token_mint
+///  12. observation_state (writable)
+///
+/// `DexId::MeteoraDlmm` (DLMM `swap`):
+///   3. lb_pair (writable)
+///   4. bin_array_bitmap_extension (DLMM program id if unused)
+///   5. reserve_x (writable)
+///   6. reserve_y (writable)
+///   7. token_x_mint
+///   8. token_y_mint
+///   9. oracle (writable)
+///  10. host_fee_in (writable, DLMM program id if unused)
+///  11. token_x_program
+///  12. token_y_program
+///  13. event_authority
+///  14. bin_array_0 (writable)
+///  15. bin_array_1 (writable)
+///  16. bin_array_2 (writable)
+///
+/// `DexId::Phoenix` (immediate-or-cancel `Swap`):
+///   3. log_authority
+///   4. market (writable)
+///   5. base_vault (writable)
+///   6. quote_vault (writable)
+///
+/// `DexId::OpenBookV2` (`place_take_order`):
+///   3. market_authority
+///   4. market (writable)
+///   5. bids (writable)
+///   6. asks (writable)
+///   7. market_base_vault (writable)
+///   8. market_quote_vault (writable)
+///   9. event_heap (writable)
+///  10. oracle_a (OpenBook program id if unused)
+///  11. oracle_b (OpenBook program id if unused)
+///  12. system_program
+///
+/// `DexId::Jupiter` (v6 `route` / `shared_accounts_route`):
+///   3.. the instruction accounts returned by the Jupiter swap-instructions API, forwarded
+///       verbatim; `JupiterRoutePlan::account_count` of them
+impl DexId {
+    pub fn hop_account_count(&self) -> usize {
+        match self {
+            DexId::Orca => 15,
+            DexId::Raydium => 17,
+            DexId::Jupiter => 3,
+            DexId::RaydiumClmm => 16,
+            DexId::RaydiumCpmm => 13,
+            DexId::MeteoraDlmm => 17,
+            DexId::Phoenix => 7,
+            DexId::OpenBookV2 => 13,
+        }
+    }
+
+    /// Index of the pool / market account within the hop, or `None` for aggregator legs
+    pub fn pool_account_index(&self) -> Option<usize> {
+        match self {
+            DexId::Orca => Some(3),
+            DexId::Raydium => Some(3),
+            DexId::Jupiter => None,
+            DexId::RaydiumClmm => Some(4),
+            DexId::RaydiumCpmm => Some(5),
+            DexId::MeteoraDlmm => Some(3),
+            DexId::Phoenix => Some(4),
+            DexId::OpenBookV2 => Some(4),
+        }
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            DexId::Orca => WHIRLPOOL_PROGRAM_ID,
+            DexId::Raydium => RAYDIUM_AMM_PROGRAM_ID,
+            DexId::Jupiter => JUPITER_PROGRAM_ID,
+            DexId::RaydiumClmm => RAYDIUM_CLMM_PROGRAM_ID,
+            DexId::RaydiumCpmm => RAYDIUM_CPMM_PROGRAM_ID,
+            DexId::MeteoraDlmm => METEORA_DLMM_PROGRAM_ID,
+            DexId::Phoenix => PHOENIX_PROGRAM_ID,
+            DexId::OpenBookV2 => OPENBOOK_V2_PROGRAM_ID,
+        }
+    }
+}
+
+// Events
+#[event]
+pub struct ArbitrageStateInitialized {
+    pub state_id: Pubkey,
+    pub authority: Pubkey,
+    pub min_execution_interval: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StateMigrated {
+    pub arbitrage_state: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArbitrageExecuted {
+    pub user: Pubkey,
+    pub profit: u64,
+    pub routes: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArbitrageFailed {
+    pub user: Pubkey,
+    pub pnl: i64,
+    pub min_profit: u64,
+    pub consecutive_failures: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub arbitrage_state: Pubkey,
+    pub reason: CircuitBreakerReason,
+    pub daily_pnl: i64,
+    pub consecutive_failures: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashLoanArbitrageExecuted {
+    pub user: Pubkey,
+    pub flash_loan_amount: u64,
+    pub profit: u64,
+    pub routes: u8,
+    pub timestamp: i64,
+}
+
+// 🌊 NEW! Orca Swap Event
+#[event]
+pub struct OrcaSwapExecuted {
+    pub user: Pubkey,
+    pub whirlpool: Pubkey,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub a_to_b: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrcaTwoHopSwapExecuted {
+    pub user: Pubkey,
+    pub whirlpool_one: Pubkey,
+    pub whirlpool_two: Pubkey,
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RaydiumSwapExecuted {
+    pub user: Pubkey,
+    pub amm: Pubkey,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BotPaused {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub authority: Pubkey,
+    pub guardian: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BotResumed {
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BotConfigUpdated {
+    pub authority: Pubkey,
+    pub change: ConfigChange,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub arbitrage_state: Pubkey,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub arbitrage_state: Pubkey,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub arbitrage_state: Pubkey,
+    pub change_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProfitsWithdrawn {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OperatorAdded {
+    pub authority: Pubkey,
+    pub operator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OperatorRemoved {
+    pub authority: Pubkey,
+    pub operator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintAllowlistUpdated {
+    pub arbitrage_state: Pubkey,
+    pub mint: Pubkey,
+    pub allowed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolAllowlistUpdated {
+    pub arbitrage_state: Pubkey,
+    pub pool: Pubkey,
+    pub allowed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProfitVaultInitialized {
+    pub arbitrage_state: Pubkey,
+    pub mint: Pubkey,
+    pub profit_vault: Pubkey,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum ArbitrageError {
+    #[msg("Invalid amount provided")]
+    InvalidAmount,
+    #[msg("No swap routes provided")]
+    EmptyRoutes,
+    #[msg("Too many swap hops for the configured hop cap")]
+    TooManyHops,
+    #[msg("Bot is currently paused")]
+    BotPaused,
+    #[msg("Execution too frequent - rate limited")]
+    ExecutionTooFrequent,
+    #[msg("Insufficient profit after execution")]
+    InsufficientProfit,
+    #[msg("Insufficient balance for operation")]
+    InsufficientBalance,
+    #[msg("Unauthorized access")]
+    Unauthorized,
+    #[msg("Slippage tolerance exceeded")]
+    SlippageExceeded,
+    #[msg("Pool liquidity insufficient")]
+    InsufficientLiquidity,
+    #[msg("Invalid swap pair - same input and output mint")]
+    InvalidSwapPair,
+    #[msg("Account validation failed")]
+    AccountValidationFailed,
+    #[msg("Arithmetic overflow or underflow")]
+    ArithmeticError,
+    #[msg("Not enough remaining accounts for swap route")]
+    MissingRouteAccounts,
+    #[msg("DEX program account does not match route")]
+    InvalidDexProgram,
+    #[msg("Jupiter route is missing its route plan")]
+    MissingRoutePlan,
+    #[msg("Operator is already registered")]
+    OperatorAlreadyAdded,
+    #[msg("Operator allowlist is full")]
+    TooManyOperators,
+    #[msg("Operator is not registered")]
+    OperatorNotFound,
+    #[msg("Config change is empty or out of range")]
+    InvalidConfigChange,
+    #[msg("Config change loosens limits and must be queued")]
+    ConfigChangeRequiresTimelock,
+    #[msg("Queued config change is still timelocked")]
+    ConfigChangeNotReady,
+    #[msg("Input amount exceeds the notional limit for this mint")]
+    NotionalLimitExceeded,
+    #[msg("Realized profit is below the minimum profit in basis points")]
+    ProfitBelowMinimumBps,
+    #[msg("Mint notional limit list is full")]
+    TooManyMintLimits,
+    #[msg("Mint is not on the allowlist")]
+    MintNotAllowed,
+    #[msg("Pool is not on the allowlist")]
+    PoolNotAllowed,
+    #[msg("State account layout version is not supported")]
+    UnsupportedStateVersion,
+    #[msg("State account is already at the current version")]
+    StateAlreadyMigrated,
+    #[msg("Route hop does not start in the mint the previous hop ended in")]
+    BrokenRouteChain,
+    #[msg("Route does not end in the mint it started with")]
+    RouteNotClosed,
+    #[msg("Opportunity expired - current slot is past max_slot")]
+    OpportunityExpired,
+    #[msg("Flash loan arbitrage must sit between a matching top-level Solend FlashBorrow and FlashRepay")]
+    FlashLoanNotPaired,
+    #[msg("Jupiter route plan supplied for a non-Jupiter hop")]
+    UnexpectedRoutePlan,
+    #[msg("Jupiter legs are disabled - enable allow_aggregator_legs through a config change")]
+    AggregatorLegNotAllowed,
+    #[msg("Route must start from and end in the user token account profit is measured on")]
+    RouteEndpointMismatch,
+    #[msg("Flash loan reserve is not a Solend reserve account")]
+    InvalidFlashLoanReserve,
+    #[msg("Hop token account does not hold the mint the route declares")]
+    TokenAccountMintMismatch,
+}
+
+// 🌊 Orca Whirlpool CPI module - simplified version for direct invoke
+pub mod whirlpool_swap {
+    use super::*;
+
+    pub const SWAP_DISCRIMINATOR: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+
+    // Price bounds accepted by the Whirlpool program
+    pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
+    pub const MAX_SQRT_PRICE_X64: u128 = 79226673515401279992447579055;
+
+    // Byte offsets of the mints in the Whirlpool account data
+    const TOKEN_MINT_A_OFFSET: usize = 101;
+    const TOKEN_MINT_B_OFFSET: usize = 181;
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct SwapInstruction {
+        pub amount: u64,
+        pub other_amount_threshold: u64,
+        pub sqrt_price_limit: u128,
+        pub amount_specified_is_input: bool,
+        pub a_to_b: bool,
+    }
+
+    impl SwapInstruction {
+        pub fn data(&self) -> Result<Vec<u8>> {
+            let mut data = SWAP_DISCRIMINATOR.to_vec();
+            data.append(&mut self.try_to_vec()?);
+            Ok(data)
+        }
+    }
+
+    pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+    pub const TWO_HOP_SWAP_V2_DISCRIMINATOR: [u8; 8] = [0xba, 0x8f, 0xd1, 0x1d, 0xfe, 0x02, 0xc2, 0x75];
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+    pub enum AccountsType {
+        TransferHookA,
+        TransferHookB,
+        TransferHookReward,
+        TransferHookInput,
+        TransferHookIntermediate,
+        TransferHookOutput,
+        SupplementalTickArrays,
+        SupplementalTickArraysOne,
+        SupplementalTickArraysTwo,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+    pub struct RemainingAccountsSlice {
+        pub accounts_type: AccountsType,
+        pub length: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+    pub struct RemainingAccountsInfo {
+        pub slices: Vec<RemainingAccountsSlice>,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct SwapV2Instruction {
+        pub amount: u64,
+        pub other_amount_threshold: u64,
+        pub sqrt_price_limit: u128,
+        pub amount_specified_is_input: bool,
+        pub a_to_b: bool,
+        pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct TwoHopSwapV2Instruction {
+        pub amount: u64,
+        pub other_amount_threshold: u64,
+        pub amount_specified_is_input: bool,
+        pub a_to_b_one: bool,
+        pub a_to_b_two: bool,
+        pub sqrt_price_limit_one: u128,
+        pub sqrt_price_limit_two: u128,
+        pub remaining_accounts_info: Option<RemainingAccountsInfo>,
+    }
+
+    /// Account keys for `swap_v2`, in the order the Whirlpool program expects them
+    pub struct SwapV2Keys {
+        pub token_program_a: Pubkey,
+        pub token_program_b: Pubkey,
+        pub memo_program: Pubkey,
+        pub token_authority: Pubkey,
+        pub whirlpool: Pubkey,
+        pub token_mint_a: Pubkey,
+        pub token_mint_b: Pubkey,
+        pub token_owner_account_a: Pubkey,
+        pub token_vault_a: Pubkey,
+        pub token_owner_account_b: Pubkey,
+        pub token_vault_b: Pubkey,
+        pub tick_array_0: Pubkey,
+        pub tick_array_1: Pubkey,
+        pub tick_array_2: Pubkey,
+        pub oracle: Pubkey,
+    }
+
+    /// Account keys for `two_hop_swap_v2`, in the order the Whirlpool program expects them
+    pub struct TwoHopSwapV2Keys {
+        pub whirlpool_one: Pubkey,
+        pub whirlpool_two: Pubkey,
+        pub token_mint_input: Pubkey,
+        pub token_mint_intermediate: Pubkey,
+        pub token_mint_output: Pubkey,
+        pub token_program_input: Pubkey,
+        pub token_program_intermediate: Pubkey,
+        pub token_program_output: Pubkey,
+        pub token_owner_account_input: Pubkey,
+        pub token_vault_one_input: Pubkey,
+        pub token_vault_one_intermediate: Pubkey,
+        pub token_vault_two_intermediate: Pubkey,
+        pub token_vault_two_output: Pubkey,
+        pub token_owner_account_output: Pubkey,
+        pub token_authority: Pubkey,
+        pub tick_array_one_0: Pubkey,
+        pub tick_array_one_1: Pubkey,
+        pub tick_array_one_2: Pubkey,
+        pub tick_array_two_0: Pubkey,
+        pub tick_array_two_1: Pubkey,
+        pub tick_array_two_2: Pubkey,
+        pub oracle_one: Pubkey,
+        pub oracle_two: Pubkey,
+        pub memo_program: Pubkey,
+    }
+
+    pub fn swap_v2_instruction(keys: &SwapV2Keys, args: &SwapV2Instruction) -> Result<Instruction> {
+        let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: WHIRLPOOL_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(keys.token_program_a, false),
+                AccountMeta::new_readonly(keys.token_program_b, false),
+                AccountMeta::new_readonly(keys.memo_program, false),
+                AccountMeta::new_readonly(keys.token_authority, true),
+                AccountMeta::new(keys.whirlpool, false),
+                AccountMeta::new_readonly(keys.token_mint_a, false),
+                AccountMeta::new_readonly(keys.token_mint_b, false),
+                AccountMeta::new(keys.token_owner_account_a, false),
+                AccountMeta::new(keys.token_vault_a, false),
+                AccountMeta::new(keys.token_owner_account_b, false),
+                AccountMeta::new(keys.token_vault_b, false),
+                AccountMeta::new(keys.tick_array_0, false),
+                AccountMeta::new(keys.tick_array_1, false),
+                AccountMeta::new(keys.tick_array_2, false),
+                AccountMeta::new(keys.oracle, false),
+            ],
+            data,
+        })
+    }
+
+    pub fn two_hop_swap_v2_instruction(keys: &TwoHopSwapV2Keys, args: &TwoHopSwapV2Instruction) -> Result<Instruction> {
+        let mut data = TWO_HOP_SWAP_V2_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: WHIRLPOOL_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(keys.whirlpool_one, false),
+                AccountMeta::new(keys.whirlpool_two, false),
+                AccountMeta::new_readonly(keys.token_mint_input, false),
+                AccountMeta::new_readonly(keys.token_mint_intermediate, false),
+                AccountMeta::new_readonly(keys.token_mint_output, false),
+                AccountMeta::new_readonly(keys.token_program_input, false),
+                AccountMeta::new_readonly(keys.token_program_intermediate, false),
+                AccountMeta::new_readonly(keys.token_program_output, false),
+                AccountMeta::new(keys.token_owner_account_input, false),
+                AccountMeta::new(keys.token_vault_one_input, false),
+                AccountMeta::new(keys.token_vault_one_intermediate, false),
+                AccountMeta::new(keys.token_vault_two_intermediate, false),
+                AccountMeta::new(keys.token_vault_two_output, false),
+                AccountMeta::new(keys.token_owner_account_output, false),
+                AccountMeta::new_readonly(keys.token_authority, true),
+                AccountMeta::new(keys.tick_array_one_0, false),
+                AccountMeta::new(keys.tick_array_one_1, false),
+                AccountMeta::new(keys.tick_array_one_2, false),
+                AccountMeta::new(keys.tick_array_two_0, false),
+                AccountMeta::new(keys.tick_array_two_1, false),
+                AccountMeta::new(keys.tick_array_two_2, false),
+                AccountMeta::new(keys.oracle_one, false),
+                AccountMeta::new(keys.oracle_two, false),
+                AccountMeta::new_readonly(keys.memo_program, false),
+            ],
+            data,
+        })
+    }
+
+    /// Read (token_mint_a, token_mint_b) from a Whirlpool account owned by the Whirlpool program
+    pub fn read_pool_mints(whirlpool: &AccountInfo) -> Result<(Pubkey, Pubkey)> {
+        require_keys_eq!(*whirlpool.owner, WHIRLPOOL_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+        let data = whirlpool.try_borrow_data()?;
+        require!(data.len() >= TOKEN_MINT_B_OFFSET + 32, ArbitrageError::AccountValidationFailed);
+        let mint_a = Pubkey::try_from(&data[TOKEN_MINT_A_OFFSET..TOKEN_MINT_A_OFFSET + 32]).unwrap();
+        let mint_b = Pubkey::try_from(&data[TOKEN_MINT_B_OFFSET..TOKEN_MINT_B_OFFSET + 32]).unwrap();
+        Ok((mint_a, mint_b))
+    }
+}
+
+// ⚡ Raydium AMM v4 CPI module
+pub mod raydium_amm {
+    use super::*;
+
+    pub const SWAP_BASE_IN_TAG: u8 = 9;
+
+    /// Account keys for `SwapBaseIn`, in the order the AMM program expects them
+    pub struct SwapBaseInKeys {
+        pub token_program: Pubkey,
+        pub amm: Pubkey,
+        pub amm_authority: Pubkey,
+        pub amm_open_orders: Pubkey,
+        pub amm_target_orders: Pubkey,
+        pub pool_coin_token_account: Pubkey,
+        pub pool_pc_token_account: Pubkey,
+        pub serum_program: Pubkey,
+        pub serum_market: Pubkey,
+        pub serum_bids: Pubkey,
+        pub serum_asks: Pubkey,
+        pub serum_event_queue: Pubkey,
+        pub serum_coin_vault: Pubkey,
+        pub serum_pc_vault: Pubkey,
+        pub serum_vault_signer: Pubkey,
+        pub user_source_token_account: Pubkey,
+        pub user_destination_token_account: Pubkey,
+        pub user_owner: Pubkey,
+    }
+
+    pub fn swap_base_in_instruction(keys: &SwapBaseInKeys, amount_in: u64, minimum_amount_out: u64) -> Instruction {
+        Instruction {
+            program_id: RAYDIUM_AMM_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(keys.token_program, false),
+                AccountMeta::new(keys.amm, false),
+                AccountMeta::new_readonly(keys.amm_authority, false),
+                AccountMeta::new(keys.amm_open_orders, false),
+                AccountMeta::new(keys.amm_target_orders, false),
+                AccountMeta::new(keys.pool_coin_token_account, false),
+                AccountMeta::new(keys.pool_pc_token_account, false),
+                AccountMeta::new_readonly(keys.serum_program, false),
+                AccountMeta::new(keys.serum_market, false),
+                AccountMeta::new(keys.serum_bids, false),
+                AccountMeta::new(keys.serum_asks, false),
+                AccountMeta::new(keys.serum_event_queue, false),
+                AccountMeta::new(keys.serum_coin_vault, false),
+                AccountMeta::new(keys.serum_pc_vault, false),
+                AccountMeta::new_readonly(keys.serum_vault_signer, false),
+                AccountMeta::new(keys.user_source_token_account, false),
+                AccountMeta::new(keys.user_destination_token_account, false),
+                AccountMeta::new_readonly(keys.user_owner, true),
+            ],
+            data: swap_base_in_data(amount_in, minimum_amount_out),
+        }
+    }
+
+    pub fn swap_base_in_data(amount_in: u64, minimum_amount_out: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(17);
+        data.push(SWAP_BASE_IN_TAG);
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+        data
+    }
+}
+
+// 🎯 Raydium CLMM CPI module
+pub mod raydium_clmm {
+    use super::*;
+
+    pub const SWAP_V2_DISCRIMINATOR: [u8; 8] = [0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct SwapV2Instruction {
+        pub amount: u64,
+        pub other_amount_threshold: u64,
+        /// Zero lets the CLMM program apply its own min/max price bound
+        pub sqrt_price_limit_x64: u128,
+        pub is_base_input: bool,
+    }
+
+    /// Account keys for `swap_v2`, in the order the CLMM program expects them
+    pub struct SwapV2Keys {
+        pub payer: Pubkey,
+        pub amm_config: Pubkey,
+        pub pool_state: Pubkey,
+        pub input_token_account: Pubkey,
+        pub output_token_account: Pubkey,
+        pub input_vault: Pubkey,
+        pub output_vault: Pubkey,
+        pub observation_state: Pubkey,
+        pub token_program: Pubkey,
+        pub token_program_2022: Pubkey,
+        pub memo_program: Pubkey,
+        pub input_vault_mint: Pubkey,
+        pub output_vault_mint: Pubkey,
+        pub tick_array_bitmap_extension: Pubkey,
+        pub tick_arrays: Vec<Pubkey>,
+    }
+
+    pub fn swap_v2_instruction(keys: &SwapV2Keys, args: &SwapV2Instruction) -> Result<Instruction> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(keys.payer, true),
+            AccountMeta::new_readonly(keys.amm_config, false),
+            AccountMeta::new(keys.pool_state, false),
+            AccountMeta::new(keys.input_token_account, false),
+            AccountMeta::new(keys.output_token_account, false),
+            AccountMeta::new(keys.input_vault, false),
+            AccountMeta::new(keys.output_vault, false),
+            AccountMeta::new(keys.observation_state, false),
+            AccountMeta::new_readonly(keys.token_program, false),
+            AccountMeta::new_readonly(keys.token_program_2022, false),
+            AccountMeta::new_readonly(keys.memo_program, false),
+            AccountMeta::new_readonly(keys.input_vault_mint, false),
+            AccountMeta::new_readonly(keys.output_vault_mint, false),
+            // Remaining accounts: bitmap extension first, then tick arrays in swap direction
+            AccountMeta::new(keys.tick_array_bitmap_extension, false),
+        ];
+        accounts.extend(keys.tick_arrays.iter().map(|tick_array| AccountMeta::new(*tick_array, false)));
+
+        let mut data = SWAP_V2_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: RAYDIUM_CLMM_PROGRAM_ID,
+            accounts,
+            data,
+        })
+    }
+}
+
+// 🧮 Raydium CP-Swap (CPMM) CPI module
+pub mod raydium_cpmm {
+    use super::*;
+
+    pub const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [0x8f, 0xbe, 0x5a, 0xda, 0xc4, 0x1e, 0x33, 0xde];
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct SwapBaseInputInstruction {
+        pub amount_in: u64,
+        pub minimum_amount_out: u64,
+    }
+
+    /// Account keys for `swap_base_input`, in the order the CP-Swap program expects them
+    pub struct SwapBaseInputKeys {
+        pub payer: Pubkey,
+        pub authority: Pubkey,
+        pub amm_config: Pubkey,
+        pub pool_state: Pubkey,
+        pub input_token_account: Pubkey,
+        pub output_token_account: Pubkey,
+        pub input_vault: Pubkey,
+        pub output_vault: Pubkey,
+        pub input_token_program: Pubkey,
+        pub output_token_program: Pubkey,
+        pub input_token_mint: Pubkey,
+        pub output_token_mint: Pubkey,
+        pub observation_state: Pubkey,
+    }
+
+    pub fn swap_base_input_instruction(
+        keys: &SwapBaseInputKeys,
+        args: &SwapBaseInputInstruction,
+    ) -> Result<Instruction> {
+        let mut data = SWAP_BASE_INPUT_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: RAYDIUM_CPMM_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(keys.payer, true),
+                AccountMeta::new_readonly(keys.authority, false),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.pool_state, false),
+                AccountMeta::new(keys.input_token_account, false),
+                AccountMeta::new(keys.output_token_account, false),
+                AccountMeta::new(keys.input_vault, false),
+                AccountMeta::new(keys.output_vault, false),
+                AccountMeta::new_readonly(keys.input_token_program, false),
+                AccountMeta::new_readonly(keys.output_token_program, false),
+                AccountMeta::new_readonly(keys.input_token_mint, false),
+                AccountMeta::new_readonly(keys.output_token_mint, false),
+                AccountMeta::new(keys.observation_state, false),
+            ],
+            data,
+        })
+    }
+}
+
+// ☄️ Meteora DLMM CPI module
+pub mod meteora_dlmm {
+    use super::*;
+
+    pub const SWAP_DISCRIMINATOR: [u8; 8] = [0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+
+    // Byte offsets in the LbPair account data
+    const TOKEN_X_MINT_OFFSET: usize = 88;
+    const TOKEN_Y_MINT_OFFSET: usize = 120;
+    const RESERVE_X_OFFSET: usize = 152;
+    const RESERVE_Y_OFFSET: usize = 184;
+
+    #[derive(AnchorSerialize, AnchorDeserialize)]
+    pub struct SwapInstruction {
+        pub amount_in: u64,
+        pub min_amount_out: u64,
+    }
+
+    /// Account keys for `swap`, in the order the DLMM program expects them
+    pub struct SwapKeys {
+        pub lb_pair: Pubkey,
+        pub bin_array_bitmap_extension: Pubkey,
+        pub reserve_x: Pubkey,
+        pub reserve_y: Pubkey,
+        pub user_token_in: Pubkey,
+        pub user_token_out: Pubkey,
+        pub token_x_mint: Pubkey,
+        pub token_y_mint: Pubkey,
+        pub oracle: Pubkey,
+        pub host_fee_in: Pubkey,
+        pub user: Pubkey,
+        pub token_x_program: Pubkey,
+        pub token_y_program: Pubkey,
+        pub event_authority: Pubkey,
+        pub bin_arrays: Vec<Pubkey>,
+    }
+
+    pub fn swap_instruction(keys: &SwapKeys, args: &SwapInstruction) -> Result<Instruction> {
+        let mut accounts = vec![
+            AccountMeta::new(keys.lb_pair, false),
+            AccountMeta::new_readonly(keys.bin_array_bitmap_extension, false),
+            AccountMeta::new(keys.reserve_x, false),
+            AccountMeta::new(keys.reserve_y, false),
+            AccountMeta::new(keys.user_token_in, false),
+            AccountMeta::new(keys.user_token_out, false),
+            AccountMeta::new_readonly(keys.token_x_mint, false),
+            AccountMeta::new_readonly(keys.token_y_mint, false),
+            AccountMeta::new(keys.oracle, false),
+            AccountMeta::new(keys.host_fee_in, false),
+            AccountMeta::new_readonly(keys.user, true),
+            AccountMeta::new_readonly(keys.token_x_program, false),
+            AccountMeta::new_readonly(keys.token_y_program, false),
+            AccountMeta::new_readonly(keys.event_authority, false),
+            AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+        ];
+        // Bin arrays are read by DLMM from its remaining accounts
+        accounts.extend(keys.bin_arrays.iter().map(|bin_array| AccountMeta::new(*bin_array, false)));
+
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: METEORA_DLMM_PROGRAM_ID,
+            accounts,
+            data,
+        })
+    }
+
+    fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+        Pubkey::try_from(&data[offset..offset + 32]).unwrap()
+    }
+
+    /// Validate a DLMM hop against the LbPair state before CPI (see the `DexId` account layout)
+    pub fn validate_accounts(route: &SwapRoute, hop_accounts: &[AccountInfo]) -> Result<()> {
+        let lb_pair = &hop_accounts[3];
+        require_keys_eq!(*lb_pair.owner, METEORA_DLMM_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+
+        let data = lb_pair.try_borrow_data()?;
+        require!(data.len() >= RESERVE_Y_OFFSET + 32, ArbitrageError::AccountValidationFailed);
+
+        let token_x_mint = read_pubkey(&data, TOKEN_X_MINT_OFFSET);
+        let token_y_mint = read_pubkey(&data, TOKEN_Y_MINT_OFFSET);
+        require_keys_eq!(hop_accounts[5].key(), read_pubkey(&data, RESERVE_X_OFFSET), ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(hop_accounts[6].key(), read_pubkey(&data, RESERVE_Y_OFFSET), ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(hop_accounts[7].key(), token_x_mint, ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(hop_accounts[8].key(), token_y_mint, ArbitrageError::AccountValidationFailed);
+
+        require!(
+            (route.input_mint == token_x_mint && route.output_mint == token_y_mint)
+                || (route.input_mint == token_y_mint && route.output_mint == token_x_mint),
+            ArbitrageError::InvalidSwapPair
+        );
+
+        for token_program_account in &hop_accounts[11..13] {
+            require!(
+                token_program_account.key() == TOKEN_PROGRAM_ID
+                    || token_program_account.key() == TOKEN_2022_PROGRAM_ID,
+                ArbitrageError::AccountValidationFailed
+            );
+        }
+        Ok(())
+    }
+}
+
+// 🔥 Phoenix order book CPI module
+pub mod phoenix {
+    use super::*;
+
+    pub const SWAP_TAG: u8 = 0;
+    pub const IMMEDIATE_OR_CANCEL_PACKET_TAG: u8 = 2;
+
+    // Byte offsets in the Phoenix MarketHeader
+    const BASE_MINT_OFFSET: usize = 48;
+    const BASE_VAULT_OFFSET: usize = 80;
+    const BASE_LOT_SIZE_OFFSET: usize = 112;
+    const QUOTE_MINT_OFFSET: usize = 128;
+    const QUOTE_VAULT_OFFSET: usize = 160;
+    const QUOTE_LOT_SIZE_OFFSET: usize = 192;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+    pub enum Side {
+        Bid,
+        Ask,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+    pub enum SelfTradeBehavior {
+        Abort,
+        CancelProvide,
+        DecrementTake,
+    }
+
+    /// Fields of `OrderPacket::ImmediateOrCancel`
+    #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+    pub struct ImmediateOrCancelOrder {
+        pub side: Side,
+        pub price_in_ticks: Option<u64>,
+        pub num_base_lots: u64,
+        pub num_quote_lots: u64,
+        pub min_base_lots_to_fill: u64,
+        pub min_quote_lots_to_fill: u64,
+        pub self_trade_behavior: SelfTradeBehavior,
+        pub match_limit: Option<u64>,
+        pub client_order_id: u128,
+        pub use_only_deposited_funds: bool,
+        pub last_valid_slot: Option<u64>,
+        pub last_valid_unix_timestamp_in_seconds: Option<u64>,
+    }
+
+    pub struct MarketHeader {
+        pub base_mint: Pubkey,
+        pub base_vault: Pubkey,
+        pub base_lot_size: u64,
+        pub quote_mint: Pubkey,
+        pub quote_vault: Pubkey,
+        pub quote_lot_size: u64,
+    }
+
+    /// Account keys for `Swap`, in the order the Phoenix program expects them
+    pub struct SwapKeys {
+        pub log_authority: Pubkey,
+        pub market: Pubkey,
+        pub trader: Pubkey,
+        pub base_account: Pubkey,
+        pub quote_account: Pubkey,
+        pub base_vault: Pubkey,
+        pub quote_vault: Pubkey,
+        pub token_program: Pubkey,
+    }
+
+    fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+        Pubkey::try_from(&data[offset..offset + 32]).unwrap()
+    }
+
+    fn read_u64(data: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    }
+
+    pub fn read_market_header(market: &AccountInfo) -> Result<MarketHeader> {
+        require_keys_eq!(*market.owner, PHOENIX_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+        let data = market.try_borrow_data()?;
+        require!(data.len() >= QUOTE_LOT_SIZE_OFFSET + 8, ArbitrageError::AccountValidationFailed);
+
+        Ok(MarketHeader {
+            base_mint: read_pubkey(&data, BASE_MINT_OFFSET),
+            base_vault: read_pubkey(&data, BASE_VAULT_OFFSET),
+            base_lot_size: read_u64(&data, BASE_LOT_SIZE_OFFSET),
+            quote_mint: read_pubkey(&data, QUOTE_MINT_OFFSET),
+            quote_vault: read_pubkey(&data, QUOTE_VAULT_OFFSET),
+            quote_lot_size: read_u64(&data, QUOTE_LOT_SIZE_OFFSET),
+        })
+    }
+
+    /// Build an IOC order that spends `amount_in` atoms and must fill at least `min_amount_out` atoms
+    pub fn immediate_or_cancel_order(
+        header: &MarketHeader,
+        side: Side,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<ImmediateOrCancelOrder> {
+        require!(header.base_lot_size > 0 && header.quote_lot_size > 0, ArbitrageError::AccountValidationFailed);
+
+        let (num_base_lots, num_quote_lots, min_base_lots_to_fill, min_quote_lots_to_fill) = match side {
+            // Spend quote, receive base
+            Side::Bid => (
+                0,
+                amount_in / header.quote_lot_size,
+                min_amount_out.div_ceil(header.base_lot_size),
+                0,
+            ),
+            // Spend base, receive quote
+            Side::Ask => (
+                amount_in / header.base_lot_size,
+                0,
+                0,
+                min_amount_out.div_ceil(header.quote_lot_size),
+            ),
+        };
+        require!(num_base_lots > 0 || num_quote_lots > 0, ArbitrageError::InvalidAmount);
+
+        Ok(ImmediateOrCancelOrder {
+            side,
+            price_in_ticks: None,
+            num_base_lots,
+            num_quote_lots,
+            min_base_lots_to_fill,
+            min_quote_lots_to_fill,
+            self_trade_behavior: SelfTradeBehavior::Abort,
+            match_limit: None,
+            client_order_id: 0,
+            use_only_deposited_funds: false,
+            last_valid_slot: None,
+            last_valid_unix_timestamp_in_seconds: None,
+        })
+    }
+
+    pub fn swap_instruction(keys: &SwapKeys, order: &ImmediateOrCancelOrder) -> Result<Instruction> {
+        let mut data = vec![SWAP_TAG, IMMEDIATE_OR_CANCEL_PACKET_TAG];
+        data.append(&mut order.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: PHOENIX_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(PHOENIX_PROGRAM_ID, false),
+                AccountMeta::new_readonly(keys.log_authority, false),
+                AccountMeta::new(keys.market, false),
+                AccountMeta::new_readonly(keys.trader, true),
+                AccountMeta::new(keys.base_account, false),
+                AccountMeta::new(keys.quote_account, false),
+                AccountMeta::new(keys.base_vault, false),
+                AccountMeta::new(keys.quote_vault, false),
+                AccountMeta::new_readonly(keys.token_program, false),
+            ],
+            data,
+        })
+    }
+}
+
+// 📖 OpenBook v2 CPI module
+pub mod openbook_v2 {
+    use super::*;
+
+    pub const PLACE_TAKE_ORDER_DISCRIMINATOR: [u8; 8] = [0x03, 0x2c, 0x47, 0x03, 0x1a, 0xc7, 0xcb, 0x55];
+
+    // Maximum number of maker orders matched by one take order
+    pub const MATCH_LIMIT: u8 = 50;
+
+    // Byte offsets in the OpenBook v2 Market account data
+    const BIDS_OFFSET: usize = 200;
+    const ASKS_OFFSET: usize = 232;
+    const EVENT_HEAP_OFFSET: usize = 264;
+    const QUOTE_LOT_SIZE_OFFSET: usize = 736;
+    const BASE_LOT_SIZE_OFFSET: usize = 744;
+    const BASE_MINT_OFFSET: usize = 864;
+    const QUOTE_MINT_OFFSET: usize = 896;
+    const MARKET_BASE_VAULT_OFFSET: usize = 928;
+    const MARKET_QUOTE_VAULT_OFFSET: usize = 968;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+    pub enum Side {
+        Bid,
+        Ask,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+    pub enum PlaceOrderType {
+        Limit,
+        ImmediateOrCancel,
+        PostOnly,
+        Market,
+        PostOnlySlide,
+        FillOrKill,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+    pub struct PlaceTakeOrderArgs {
+        pub side: Side,
+        pub price_lots: i64,
+        pub max_base_lots: i64,
+        pub max_quote_lots_including_fees: i64,
+        pub order_type: PlaceOrderType,
+        pub limit: u8,
+    }
+
+    pub struct Market {
+        pub bids: Pubkey,
+        pub asks: Pubkey,
+        pub event_heap: Pubkey,
+        pub quote_lot_size: i64,
+        pub base_lot_size: i64,
+        pub base_mint: Pubkey,
+        pub quote_mint: Pubkey,
+        pub market_base_vault: Pubkey,
+        pub market_quote_vault: Pubkey,
+    }
+
+    /// Account keys for `place_take_order`, in the order the OpenBook v2 program expects them
+    pub struct PlaceTakeOrderKeys {
+        pub signer: Pubkey,
+        pub market: Pubkey,
+        pub market_authority: Pubkey,
+        pub bids: Pubkey,
+        pub asks: Pubkey,
+        pub market_base_vault: Pubkey,
+        pub market_quote_vault: Pubkey,
+        pub event_heap: Pubkey,
+        pub user_base_account: Pubkey,
+        pub user_quote_account: Pubkey,
+        pub oracle_a: Pubkey,
+        pub oracle_b: Pubkey,
+        pub token_program: Pubkey,
+        pub system_program: Pubkey,
+    }
+
+    fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+        Pubkey::try_from(&data[offset..offset + 32]).unwrap()
+    }
+
+    fn read_i64(data: &[u8], offset: usize) -> i64 {
+        i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+    }
+
+    pub fn read_market(market: &AccountInfo) -> Result<Market> {
+        require_keys_eq!(*market.owner, OPENBOOK_V2_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+        let data = market.try_borrow_data()?;
+        require!(data.len() >= MARKET_QUOTE_VAULT_OFFSET + 32, ArbitrageError::AccountValidationFailed);
+
+        Ok(Market {
+            bids: read_pubkey(&data, BIDS_OFFSET),
+            asks: read_pubkey(&data, ASKS_OFFSET),
+            event_heap: read_pubkey(&data, EVENT_HEAP_OFFSET),
+            quote_lot_size: read_i64(&data, QUOTE_LOT_SIZE_OFFSET),
+            base_lot_size: read_i64(&data, BASE_LOT_SIZE_OFFSET),
+            base_mint: read_pubkey(&data, BASE_MINT_OFFSET),
+            quote_mint: read_pubkey(&data, QUOTE_MINT_OFFSET),
+            market_base_vault: read_pubkey(&data, MARKET_BASE_VAULT_OFFSET),
+            market_quote_vault: read_pubkey(&data, MARKET_QUOTE_VAULT_OFFSET),
+        })
+    }
+
+    /// Build a market take order that spends at most `amount_in` atoms of the input side
+    pub fn take_order_args(market: &Market, side: Side, amount_in: u64) -> Result<PlaceTakeOrderArgs> {
+        require!(market.base_lot_size > 0 && market.quote_lot_size > 0, ArbitrageError::AccountValidationFailed);
+        let amount_in = i64::try_from(amount_in).map_err(|_| ArbitrageError::ArithmeticError)?;
+
+        let (price_lots, max_base_lots, max_quote_lots_including_fees) = match side {
+            // Spend quote, receive base: any price, bounded by quote lots
+            Side::Bid => (i64::MAX, i64::MAX, amount_in / market.quote_lot_size),
+            // Spend base, receive quote: any price, bounded by base lots
+            Side::Ask => (1, amount_in / market.base_lot_size, i64::MAX),
+        };
+        require!(max_base_lots > 0 && max_quote_lots_including_fees > 0, ArbitrageError::InvalidAmount);
+
+        Ok(PlaceTakeOrderArgs {
+            side,
+            price_lots,
+            max_base_lots,
+            max_quote_lots_including_fees,
+            order_type: PlaceOrderType::Market,
+            limit: MATCH_LIMIT,
+        })
+    }
+
+    pub fn place_take_order_instruction(keys: &PlaceTakeOrderKeys, args: &PlaceTakeOrderArgs) -> Result<Instruction> {
+        let mut data = PLACE_TAKE_ORDER_DISCRIMINATOR.to_vec();
+        data.append(&mut args.try_to_vec()?);
+
+        Ok(Instruction {
+            program_id: OPENBOOK_V2_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(keys.signer, true),
+                // The trader also pays any penalty for an unfilled take order
+                AccountMeta::new(keys.signer, true),
+                AccountMeta::new(keys.market, false),
+                AccountMeta::new_readonly(keys.market_authority, false),
+                AccountMeta::new(keys.bids, false),
+                AccountMeta::new(keys.asks, false),
+                AccountMeta::new(keys.market_base_vault, false),
+                AccountMeta::new(keys.market_quote_vault, false),
+                AccountMeta::new(keys.event_heap, false),
+                AccountMeta::new(keys.user_base_account, false),
+                AccountMeta::new(keys.user_quote_account, false),
+                AccountMeta::new_readonly(keys.oracle_a, false),
+                AccountMeta::new_readonly(keys.oracle_b, false),
+                AccountMeta::new_readonly(keys.token_program, false),
+                AccountMeta::new_readonly(keys.system_program, false),
+                // No open orders admin
+                AccountMeta::new_readonly(OPENBOOK_V2_PROGRAM_ID, false),
+            ],
+            data,
+        })
+    }
+}
+
+// 🪐 Jupiter v6 CPI module
+pub mod jupiter {
+    use super::*;
+
+    pub const ROUTE_DISCRIMINATOR: [u8; 8] = [0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a];
+    pub const SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR: [u8; 8] = [0xc1, 0x20, 0x9b, 0x33, 0x41, 0xd6, 0x9c, 0x81];
+
+    /// Instruction data with our own amounts around the opaque route plan. Slippage is zero
+    /// because `min_amount_out` is passed as the quoted output.
+    pub fn route_data(plan: &JupiterRoutePlan, in_amount: u64, quoted_out_amount: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(plan.route_plan.len() + 28);
+        match plan.shared_accounts_id {
+            Some(id) => {
+                data.extend_from_slice(&SHARED_ACCOUNTS_ROUTE_DISCRIMINATOR);
+                data.push(id);
+            },
+            None => data.extend_from_slice(&ROUTE_DISCRIMINATOR),
+        }
+        data.extend_from_slice(&plan.route_plan);
+        data.extend_from_slice(&in_amount.to_le_bytes());
+        data.extend_from_slice(&quoted_out_amount.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes()); // slippage_bps
+        data.push(0); // platform_fee_bps
+        data
+    }
+
+    /// Check that the forwarded account list trades the hop's own token accounts for the user
+    pub fn validate_accounts(
+        plan: &JupiterRoutePlan,
+        jupiter_accounts: &[AccountInfo],
+        user: &AccountInfo,
+        user_source: &AccountInfo,
+        user_destination: &AccountInfo,
+    ) -> Result<()> {
+        // (user_transfer_authority, source_token_account, destination_token_account) indices
+        let (authority_index, source_index, destination_index) = match plan.shared_accounts_id {
+            Some(_) => (2, 3, 6),
+            None => (1, 2, 3),
+        };
+        require!(jupiter_accounts.len() > destination_index, ArbitrageError::MissingRouteAccounts);
+        require_keys_eq!(jupiter_accounts[authority_index].key(), user.key(), ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(jupiter_accounts[source_index].key(), user_source.key(), ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(
+            jupiter_accounts[destination_index].key(),
+            user_destination.key(),
+            ArbitrageError::AccountValidationFailed
+        );
+        Ok(())
+    }
+}
+
+/// Record a single swap's measured spend and receipt against the input and output mint stats
+pub fn record_swap_volume(
+    source: &AccountInfo,
+    destination: &AccountInfo,
+    input_stats: &mut MintStats,
+    output_stats: &mut MintStats,
+    source_balance_before: u64,
+    destination_balance_before: u64,
+) -> Result<()> {
+    let spent = source_balance_before.saturating_sub(token_account_amount(source)?);
+    let received = token_account_amount(destination)?.saturating_sub(destination_balance_before);
+    input_stats.record_trade(spent, 0, 0)?;
+    output_stats.record_trade(0, received, 0)
+}
+
+/// `record_swap_volume` for a pool with A/B sides, oriented by the swap direction
+pub fn record_pool_swap_volume(
+    owner_account_a: &AccountInfo,
+    owner_account_b: &AccountInfo,
+    stats_a: &mut MintStats,
+    stats_b: &mut MintStats,
+    (balance_a_before, balance_b_before): (u64, u64),
+    a_to_b: bool,
+) -> Result<()> {
+    if a_to_b {
+        record_swap_volume(owner_account_a, owner_account_b, stats_a, stats_b, balance_a_before, balance_b_before)
+    } else {
+        record_swap_volume(owner_account_b, owner_account_a, stats_b, stats_a, balance_b_before, balance_a_before)
+    }
+}
+
+pub fn token_account_mint(token_account: &AccountInfo) -> Result<Pubkey> {
+    let data = token_account.try_borrow_data()?;
+    require!(data.len() >= 32, ArbitrageError::AccountValidationFailed);
+    Ok(Pubkey::new_from_array(data[..32].try_into().unwrap()))
+}
+
+/// Read the `amount` field of an SPL token account
+pub fn token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    require!(data.len() >= 72, ArbitrageError::AccountValidationFailed);
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+// ✅ Mint and pool allowlist checks for route legs
+pub const ROUTE_ALLOWLIST_ACCOUNT_COUNT: usize = 3;
+
+pub mod allowlist {
+    use super::*;
+
+    /// Deserialize an allowlist entry, which only this program can have created
+    fn load_entry<T: AccountDeserialize>(entry: &AccountInfo) -> Option<T> {
+        if *entry.owner != crate::ID {
+            return None;
+        }
+        let data = entry.try_borrow_data().ok()?;
+        T::try_deserialize(&mut &data[..]).ok()
+    }
+
+    pub fn check_pool(entry: &AccountInfo, arbitrage_state: &Pubkey, pool: &Pubkey) -> Result<()> {
+        let entry = load_entry::<AllowedPool>(entry).ok_or(ArbitrageError::PoolNotAllowed)?;
+        require!(entry.allows(arbitrage_state, pool), ArbitrageError::PoolNotAllowed);
+        Ok(())
+    }
+
+    pub fn check_mint(entry: &AccountInfo, arbitrage_state: &Pubkey, mint: &Pubkey) -> Result<()> {
+        let entry = load_entry::<AllowedMint>(entry).ok_or(ArbitrageError::MintNotAllowed)?;
+        require!(entry.allows(arbitrage_state, mint), ArbitrageError::MintNotAllowed);
+        Ok(())
+    }
+
+    /// Check a leg's pool and both mints against the entries that precede its hop accounts
+    pub fn check_route(
+        route: &SwapRoute,
+        allowlist_accounts: &[AccountInfo],
+        hop_accounts: &[AccountInfo],
+        arbitrage_state: &Account<ArbitrageState>,
+    ) -> Result<()> {
+        require!(
+            allowlist_accounts.len() == ROUTE_ALLOWLIST_ACCOUNT_COUNT && !hop_accounts.is_empty(),
+            ArbitrageError::MissingRouteAccounts
+        );
+        let state_key = arbitrage_state.key();
+        check_mint(&allowlist_accounts[1], &state_key, &route.input_mint)?;
+        check_mint(&allowlist_accounts[2], &state_key, &route.output_mint)?;
+
+        match route.dex_id.pool_account_index() {
+            Some(pool_index) => {
+                require!(hop_accounts.len() > pool_index, ArbitrageError::MissingRouteAccounts);
+                check_pool(&allowlist_accounts[0], &state_key, hop_accounts[pool_index].key)
+            }
+            // Aggregator legs pick pools the pool allowlist cannot see, so they need their own
+            // timelocked opt-in rather than a single pool entry
+            None => {
+                require!(arbitrage_state.allow_aggregator_legs, ArbitrageError::AggregatorLegNotAllowed);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Profit is measured on the user token account, so the first hop must spend from it and the
+/// last hop must pay back into it
+pub fn check_route_endpoint(
+    hop_index: usize,
+    hop_count: usize,
+    hop_accounts: &[AccountInfo],
+    user_token_account: &Pubkey,
+) -> Result<()> {
+    require!(hop_accounts.len() > 2, ArbitrageError::MissingRouteAccounts);
+    if hop_index == 0 {
+        require_keys_eq!(hop_accounts[1].key(), *user_token_account, ArbitrageError::RouteEndpointMismatch);
+    }
+    if hop_index + 1 == hop_count {
+        require_keys_eq!(hop_accounts[2].key(), *user_token_account, ArbitrageError::RouteEndpointMismatch);
+    }
+    Ok(())
+}
+
+/// The hop's source and destination token accounts must hold the mints the route declares, so
+/// the allowlist and route-chain checks on those mints cover what the hop actually trades
+pub fn check_hop_token_accounts(route: &SwapRoute, hop_accounts: &[AccountInfo]) -> Result<()> {
+    require!(hop_accounts.len() > 2, ArbitrageError::MissingRouteAccounts);
+    require_keys_eq!(
+        token_account_mint(&hop_accounts[1])?,
+        route.input_mint,
+        ArbitrageError::TokenAccountMintMismatch
+    );
+    require_keys_eq!(
+        token_account_mint(&hop_accounts[2])?,
+        route.output_mint,
+        ArbitrageError::TokenAccountMintMismatch
+    );
+    Ok(())
+}
+
+/// Bind a route chain that passed `validate_route_chain` to the accounts it executes with,
+/// before any CPI: every hop's token accounts must hold its declared mints, and the chain must
+/// start from and end in the user token account
+pub fn validate_route_accounts(
+    routes: &[SwapRoute],
+    remaining_accounts: &[AccountInfo],
+    user_token_account: &Pubkey,
+) -> Result<()> {
+    let mut remaining = remaining_accounts;
+    for (i, route) in routes.iter().enumerate() {
+        let count = ROUTE_ALLOWLIST_ACCOUNT_COUNT + route.hop_account_count();
+        require!(remaining.len() >= count, ArbitrageError::MissingRouteAccounts);
+        let (route_accounts, rest) = remaining.split_at(count);
+        remaining = rest;
+        let hop_accounts = &route_accounts[ROUTE_ALLOWLIST_ACCOUNT_COUNT..];
+
+        check_route_endpoint(i, routes.len(), hop_accounts, user_token_account)?;
+        check_hop_token_accounts(route, hop_accounts)?;
+    }
+    require!(remaining.is_empty(), ArbitrageError::AccountValidationFailed);
+    Ok(())
+}
+
+// 🔀 Route execution - dispatches each SwapRoute to its DEX via CPI
+pub fn execute_routes<'info>(
+    routes: &[SwapRoute],
+    amount_mode: RouteAmountMode,
+    remaining_accounts: &[AccountInfo<'info>],
+    arbitrage_state: &Account<ArbitrageState>,
+    user: &AccountInfo<'info>,
+    user_token_account: &Pubkey,
+    spl_token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    validate_route_accounts(routes, remaining_accounts, user_token_account)?;
+
+    let mut remaining = remaining_accounts;
+    let mut previous_output: Option<u64> = None;
+
+    for (i, route) in routes.iter().enumerate() {
+        // In chained mode every hop after the first spends what the previous hop produced
+        let mut route = route.clone();
+        if let (RouteAmountMode::ChainOutput, Some(amount_out)) = (amount_mode, previous_output) {
+            route.amount_in = amount_out;
+        }
+        require!(route.amount_in > 0, ArbitrageError::InvalidAmount);
+        arbitrage_state.check_notional_limit(&route.input_mint, route.amount_in)?;
+
+        msg!("Executing route {}/{}: {:?} swap (amount in: {})",
+             i + 1, routes.len(), route.dex_id, route.amount_in);
+
+        let count = ROUTE_ALLOWLIST_ACCOUNT_COUNT + route.hop_account_count();
+        require!(remaining.len() >= count, ArbitrageError::MissingRouteAccounts);
+        let (route_accounts, rest) = remaining.split_at(count);
+        remaining = rest;
+        let (allowlist_accounts, hop_accounts) = route_accounts.split_at(ROUTE_ALLOWLIST_ACCOUNT_COUNT);
+
+        allowlist::check_route(&route, allowlist_accounts, hop_accounts, arbitrage_state)?;
+
+        // Measure each hop's output from its destination balance; the hop's own
+        // min_amount_out holds regardless of what the venue enforces
+        let user_destination = hop_accounts.get(2).ok_or(ArbitrageError::MissingRouteAccounts)?;
+        let destination_before = token_account_amount(user_destination)?;
+        execute_swap_route(&route, hop_accounts, user, spl_token_program)?;
+        let amount_out = token_account_amount(user_destination)?.saturating_sub(destination_before);
+        require!(amount_out >= route.min_amount_out, ArbitrageError::SlippageExceeded);
+        previous_output = Some(amount_out);
+    }
+
+    require!(remaining.is_empty(), ArbitrageError::AccountValidationFailed);
+    Ok(())
+}
+
+/// Dispatch one hop to its DEX; `execute_routes` checks the hop's measured output against
+/// `min_amount_out`
+pub fn execute_swap_route<'info>(
+    route: &SwapRoute,
+    hop_accounts: &[AccountInfo<'info>],
+    user: &AccountInfo<'info>,
+    spl_token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(hop_accounts.len() == route.hop_account_count(), ArbitrageError::MissingRouteAccounts);
+    require_keys_eq!(hop_accounts[0].key(), route.dex_id.program_id(), ArbitrageError::InvalidDexProgram);
+    // Raydium AMM v4 / CLMM, Phoenix and OpenBook only accept SPL Token in their token program slot
+    require_keys_eq!(spl_token_program.key(), TOKEN_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+
+    let user_source = &hop_accounts[1];
+    let user_destination = &hop_accounts[2];
+
+    match route.dex_id {
+        DexId::Orca => {
+            msg!("🌊 Orca swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let whirlpool = &hop_accounts[3];
+            let (mint_a, mint_b) = whirlpool_swap::read_pool_mints(whirlpool)?;
+            let a_to_b = if route.input_mint == mint_a && route.output_mint == mint_b {
+                true
+            } else if route.input_mint == mint_b && route.output_mint == mint_a {
+                false
+            } else {
+                return err!(ArbitrageError::InvalidSwapPair);
+            };
+
+            let (owner_account_a, owner_account_b) = if a_to_b {
+                (user_source, user_destination)
+            } else {
+                (user_destination, user_source)
+            };
+
+            require_keys_eq!(hop_accounts[4].key(), mint_a, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[5].key(), mint_b, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[14].key(), MEMO_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+
+            let swap_ix = whirlpool_swap::swap_v2_instruction(
+                &whirlpool_swap::SwapV2Keys {
+                    token_program_a: hop_accounts[12].key(),
+                    token_program_b: hop_accounts[13].key(),
+                    memo_program: hop_accounts[14].key(),
+                    token_authority: user.key(),
+                    whirlpool: whirlpool.key(),
+                    token_mint_a: hop_accounts[4].key(),
+                    token_mint_b: hop_accounts[5].key(),
+                    token_owner_account_a: owner_account_a.key(),
+                    token_vault_a: hop_accounts[6].key(),
+                    token_owner_account_b: owner_account_b.key(),
+                    token_vault_b: hop_accounts[7].key(),
+                    tick_array_0: hop_accounts[8].key(),
+                    tick_array_1: hop_accounts[9].key(),
+                    tick_array_2: hop_accounts[10].key(),
+                    oracle: hop_accounts[11].key(),
+                },
+                &whirlpool_swap::SwapV2Instruction {
+                    amount: route.amount_in,
+                    other_amount_threshold: route.min_amount_out,
+                    sqrt_price_limit: if a_to_b {
+                        whirlpool_swap::MIN_SQRT_PRICE_X64
+                    } else {
+                        whirlpool_swap::MAX_SQRT_PRICE_X64
+                    },
+                    amount_specified_is_input: true,
+                    a_to_b,
+                    remaining_accounts_info: None,
+                },
+            )?;
+
+            let mut account_infos = vec![user.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::Raydium => {
+            msg!("⚡ Raydium swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let swap_ix = raydium_amm::swap_base_in_instruction(
+                &raydium_amm::SwapBaseInKeys {
+                    token_program: spl_token_program.key(),
+                    amm: hop_accounts[3].key(),
+                    amm_authority: hop_accounts[4].key(),
+                    amm_open_orders: hop_accounts[5].key(),
+                    amm_target_orders: hop_accounts[6].key(),
+                    pool_coin_token_account: hop_accounts[7].key(),
+                    pool_pc_token_account: hop_accounts[8].key(),
+                    serum_program: hop_accounts[9].key(),
+                    serum_market: hop_accounts[10].key(),
+                    serum_bids: hop_accounts[11].key(),
+                    serum_asks: hop_accounts[12].key(),
+                    serum_event_queue: hop_accounts[13].key(),
+                    serum_coin_vault: hop_accounts[14].key(),
+                    serum_pc_vault: hop_accounts[15].key(),
+                    serum_vault_signer: hop_accounts[16].key(),
+                    user_source_token_account: user_source.key(),
+                    user_destination_token_account: user_destination.key(),
+                    user_owner: user.key(),
+                },
+                route.amount_in,
+                route.min_amount_out,
+            );
+
+            let mut account_infos = vec![spl_token_program.clone()];
+            account_infos.extend_from_slice(&hop_accounts[3..]);
+            account_infos.push(user_source.clone());
+            account_infos.push(user_destination.clone());
+            account_infos.push(user.clone());
+            account_infos.push(hop_accounts[0].clone());
+
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::RaydiumClmm => {
+            msg!("🎯 Raydium CLMM swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let pool_state = &hop_accounts[4];
+            require_keys_eq!(*pool_state.owner, RAYDIUM_CLMM_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[8].key(), TOKEN_2022_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[9].key(), MEMO_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[10].key(), route.input_mint, ArbitrageError::InvalidSwapPair);
+            require_keys_eq!(hop_accounts[11].key(), route.output_mint, ArbitrageError::InvalidSwapPair);
+
+            let swap_ix = raydium_clmm::swap_v2_instruction(
+                &raydium_clmm::SwapV2Keys {
+                    payer: user.key(),
+                    amm_config: hop_accounts[3].key(),
+                    pool_state: pool_state.key(),
+                    input_token_account: user_source.key(),
+                    output_token_account: user_destination.key(),
+                    input_vault: hop_accounts[5].key(),
+                    output_vault: hop_accounts[6].key(),
+                    observation_state: hop_accounts[7].key(),
+                    token_program: spl_token_program.key(),
+                    token_program_2022: hop_accounts[8].key(),
+                    memo_program: hop_accounts[9].key(),
+                    input_vault_mint: hop_accounts[10].key(),
+                    output_vault_mint: hop_accounts[11].key(),
+                    tick_array_bitmap_extension: hop_accounts[12].key(),
+                    tick_arrays: hop_accounts[13..16].iter().map(|tick_array| tick_array.key()).collect(),
+                },
+                &raydium_clmm::SwapV2Instruction {
+                    amount: route.amount_in,
+                    other_amount_threshold: route.min_amount_out,
+                    sqrt_price_limit_x64: 0,
+                    is_base_input: true,
+                },
+            )?;
+
+            let mut account_infos = vec![user.clone(), spl_token_program.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::RaydiumCpmm => {
+            msg!("🧮 Raydium CPMM swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let pool_state = &hop_accounts[5];
+            require_keys_eq!(*pool_state.owner, RAYDIUM_CPMM_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+            for token_program_account in &hop_accounts[8..10] {
+                require!(
+                    token_program_account.key() == TOKEN_PROGRAM_ID
+                        || token_program_account.key() == TOKEN_2022_PROGRAM_ID,
+                    ArbitrageError::AccountValidationFailed
+                );
+            }
+            require_keys_eq!(hop_accounts[10].key(), route.input_mint, ArbitrageError::InvalidSwapPair);
+            require_keys_eq!(hop_accounts[11].key(), route.output_mint, ArbitrageError::InvalidSwapPair);
+
+            let swap_ix = raydium_cpmm::swap_base_input_instruction(
+                &raydium_cpmm::SwapBaseInputKeys {
+                    payer: user.key(),
+                    authority: hop_accounts[3].key(),
+                    amm_config: hop_accounts[4].key(),
+                    pool_state: pool_state.key(),
+                    input_token_account: user_source.key(),
+                    output_token_account: user_destination.key(),
+                    input_vault: hop_accounts[6].key(),
+                    output_vault: hop_accounts[7].key(),
+                    input_token_program: hop_accounts[8].key(),
+                    output_token_program: hop_accounts[9].key(),
+                    input_token_mint: hop_accounts[10].key(),
+                    output_token_mint: hop_accounts[11].key(),
+                    observation_state: hop_accounts[12].key(),
+                },
+                &raydium_cpmm::SwapBaseInputInstruction {
+                    amount_in: route.amount_in,
+                    minimum_amount_out: route.min_amount_out,
+                },
+            )?;
+
+            let mut account_infos = vec![user.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::MeteoraDlmm => {
+            msg!("☄️ Meteora DLMM swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            meteora_dlmm::validate_accounts(route, hop_accounts)?;
+
+            let swap_ix = meteora_dlmm::swap_instruction(
+                &meteora_dlmm::SwapKeys {
+                    lb_pair: hop_accounts[3].key(),
+                    bin_array_bitmap_extension: hop_accounts[4].key(),
+                    reserve_x: hop_accounts[5].key(),
+                    reserve_y: hop_accounts[6].key(),
+                    user_token_in: user_source.key(),
+                    user_token_out: user_destination.key(),
+                    token_x_mint: hop_accounts[7].key(),
+                    token_y_mint: hop_accounts[8].key(),
+                    oracle: hop_accounts[9].key(),
+                    host_fee_in: hop_accounts[10].key(),
+                    user: user.key(),
+                    token_x_program: hop_accounts[11].key(),
+                    token_y_program: hop_accounts[12].key(),
+                    event_authority: hop_accounts[13].key(),
+                    bin_arrays: hop_accounts[14..17].iter().map(|bin_array| bin_array.key()).collect(),
+                },
+                &meteora_dlmm::SwapInstruction {
+                    amount_in: route.amount_in,
+                    min_amount_out: route.min_amount_out,
+                },
+            )?;
+
+            let mut account_infos = vec![user.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::Phoenix => {
+            msg!("🔥 Phoenix IOC swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let market = &hop_accounts[4];
+            let header = phoenix::read_market_header(market)?;
+            require_keys_eq!(hop_accounts[5].key(), header.base_vault, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[6].key(), header.quote_vault, ArbitrageError::AccountValidationFailed);
+
+            let (side, base_account, quote_account) =
+                if route.input_mint == header.quote_mint && route.output_mint == header.base_mint {
+                    (phoenix::Side::Bid, user_destination, user_source)
+                } else if route.input_mint == header.base_mint && route.output_mint == header.quote_mint {
+                    (phoenix::Side::Ask, user_source, user_destination)
+                } else {
+                    return err!(ArbitrageError::InvalidSwapPair);
+                };
+
+            let order = phoenix::immediate_or_cancel_order(&header, side, route.amount_in, route.min_amount_out)?;
+            let swap_ix = phoenix::swap_instruction(
+                &phoenix::SwapKeys {
+                    log_authority: hop_accounts[3].key(),
+                    market: market.key(),
+                    trader: user.key(),
+                    base_account: base_account.key(),
+                    quote_account: quote_account.key(),
+                    base_vault: hop_accounts[5].key(),
+                    quote_vault: hop_accounts[6].key(),
+                    token_program: spl_token_program.key(),
+                },
+                &order,
+            )?;
+
+            let mut account_infos = vec![user.clone(), spl_token_program.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            // Lot rounding can leave the fill below min_amount_out; execute_routes enforces it
+            // on the measured balance delta
+            invoke(&swap_ix, &account_infos)?;
+        },
+        DexId::OpenBookV2 => {
+            msg!("📖 OpenBook v2 take order: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let market_account = &hop_accounts[4];
+            let market = openbook_v2::read_market(market_account)?;
+            require_keys_eq!(hop_accounts[5].key(), market.bids, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[6].key(), market.asks, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[7].key(), market.market_base_vault, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[8].key(), market.market_quote_vault, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[9].key(), market.event_heap, ArbitrageError::AccountValidationFailed);
+            require_keys_eq!(hop_accounts[12].key(), System::id(), ArbitrageError::AccountValidationFailed);
+
+            let (side, base_account, quote_account) =
+                if route.input_mint == market.quote_mint && route.output_mint == market.base_mint {
+                    (openbook_v2::Side::Bid, user_destination, user_source)
+                } else if route.input_mint == market.base_mint && route.output_mint == market.quote_mint {
+                    (openbook_v2::Side::Ask, user_source, user_destination)
+                } else {
+                    return err!(ArbitrageError::InvalidSwapPair);
+                };
+
+            let take_order_ix = openbook_v2::place_take_order_instruction(
+                &openbook_v2::PlaceTakeOrderKeys {
+                    signer: user.key(),
+                    market: market_account.key(),
+                    market_authority: hop_accounts[3].key(),
+                    bids: hop_accounts[5].key(),
+                    asks: hop_accounts[6].key(),
+                    market_base_vault: hop_accounts[7].key(),
+                    market_quote_vault: hop_accounts[8].key(),
+                    event_heap: hop_accounts[9].key(),
+                    user_base_account: base_account.key(),
+                    user_quote_account: quote_account.key(),
+                    oracle_a: hop_accounts[10].key(),
+                    oracle_b: hop_accounts[11].key(),
+                    token_program: spl_token_program.key(),
+                    system_program: hop_accounts[12].key(),
+                },
+                &openbook_v2::take_order_args(&market, side, route.amount_in)?,
+            )?;
+
+            let mut account_infos = vec![user.clone(), spl_token_program.clone()];
+            account_infos.extend_from_slice(hop_accounts);
+
+            // place_take_order has no min-out argument; execute_routes enforces it on the
+            // measured balance delta
+            invoke(&take_order_ix, &account_infos)?;
+        },
+        DexId::Jupiter => {
+            msg!("🪐 Jupiter swap: {} → {} (amount: {})",
+                 route.input_mint, route.output_mint, route.amount_in);
+
+            let plan = route.jupiter_route.as_ref().ok_or(ArbitrageError::MissingRoutePlan)?;
+            let jupiter_accounts = &hop_accounts[3..];
+            jupiter::validate_accounts(plan, jupiter_accounts, user, user_source, user_destination)?;
+
+            let route_ix = Instruction {
+                program_id: JUPITER_PROGRAM_ID,
+                // Forward the off-chain account list verbatim
+                accounts: jupiter_accounts
+                    .iter()
+                    .map(|account| AccountMeta {
+                        pubkey: account.key(),
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                    .collect(),
+                data: jupiter::route_data(plan, route.amount_in, route.min_amount_out),
+            };
+
+            invoke(&route_ix, hop_accounts)?;
+        },
+    }
+
+    msg!("  ✅ {:?} swap executed (min out: {})", route.dex_id, route.min_amount_out);
+    Ok(())
+}
+
+// 🏦 Solend Flash Loan Integration
+pub mod solend {
+    use super::*;
+
+    // LendingInstruction tags in the Solend token-lending program
+    pub const FLASH_BORROW_RESERVE_LIQUIDITY_TAG: u8 = 19;
+    pub const FLASH_REPAY_RESERVE_LIQUIDITY_TAG: u8 = 20;
+
+    // Packed Solend `Reserve` layout: the flash loan fee sits in `ReserveConfig::fees`
+    pub const RESERVE_LEN: usize = 619;
+    pub const RESERVE_VERSION: u8 = 1;
+    const FLASH_LOAN_FEE_WAD_OFFSET: usize = 314;
+    const HOST_FEE_PERCENTAGE_OFFSET: usize = 322;
+    pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+    /// Flash loan part of a reserve's `ReserveFees` config
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ReserveFees {
+        pub flash_loan_fee_wad: u64,
+        pub host_fee_percentage: u8,
+    }
+
+    impl ReserveFees {
+        /// Fee Solend adds to the repay of an `amount` flash loan: `amount * fee_wad / WAD` rounded
+        /// half up, at least 1 (2 when a host fee is carved out of it), or nothing for a free reserve
+        pub fn flash_loan_fee(&self, amount: u64) -> Result<u64> {
+            if self.flash_loan_fee_wad == 0 || amount == 0 {
+                return Ok(0);
+            }
+            let minimum_fee = if self.host_fee_percentage > 0 { 2 } else { 1 };
+            let fee = u128::from(amount)
+                .checked_mul(u128::from(self.flash_loan_fee_wad))
+                .and_then(|scaled| scaled.checked_add(WAD / 2))
+                .ok_or(ArbitrageError::ArithmeticError)?
+                / WAD;
+            let fee = u64::try_from(fee)
+                .map_err(|_| ArbitrageError::ArithmeticError)?
+                .max(minimum_fee);
+            // Solend refuses loans the fee would swallow
+            require!(fee < amount, ArbitrageError::InvalidAmount);
+            Ok(fee)
+        }
+    }
+
+    /// Decode the fee config of a reserve, which must belong to Solend
+    pub fn read_reserve_fees(reserve: &AccountInfo) -> Result<ReserveFees> {
+        require_keys_eq!(*reserve.owner, SOLEND_PROGRAM_ID, ArbitrageError::InvalidFlashLoanReserve);
+        let data = reserve.try_borrow_data()?;
+        require!(
+            data.len() == RESERVE_LEN && data[0] == RESERVE_VERSION,
+            ArbitrageError::InvalidFlashLoanReserve
+        );
+        Ok(ReserveFees {
+            flash_loan_fee_wad: u64::from_le_bytes(
+                data[FLASH_LOAN_FEE_WAD_OFFSET..HOST_FEE_PERCENTAGE_OFFSET].try_into().unwrap(),
+            ),
+            host_fee_percentage: data[HOST_FEE_PERCENTAGE_OFFSET],
+        })
+    }
+
+    /// Accounts shared by the flash borrow and flash repay instructions
+    pub struct FlashLoanAccounts {
+        pub reserve: Pubkey,
+        pub reserve_liquidity_supply: Pubkey,
+        pub reserve_liquidity_fee_receiver: Pubkey,
+        pub host_fee_receiver: Pubkey,
+        pub lending_market: Pubkey,
+        pub lending_market_authority: Pubkey,
+        pub user_token_account: Pubkey,
+        pub user_transfer_authority: Pubkey,
+        pub token_program: Pubkey,
+    }
+
+    /// Confirm the executing instruction is a top-level `flash_loan_arbitrage` between a Solend
+    /// FlashBorrow and the FlashRepay that names it, both moving `amount` of `reserve` through
+    /// `user_token_account`. Solend refuses both under CPI, so the bot places them around us.
+    pub fn verify_flash_loan_pairing(
+        instructions_sysvar: &AccountInfo,
+        reserve: &Pubkey,
+        user_token_account: &Pubkey,
+        user_transfer_authority: &Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let current_index = usize::from(load_current_index_checked(instructions_sysvar)?);
+        let current_ix = load_instruction_at_checked(current_index, instructions_sysvar)?;
+        require_keys_eq!(current_ix.program_id, crate::ID, ArbitrageError::FlashLoanNotPaired);
+
+        // Nearest FlashBorrow before us
+        let mut borrow = None;
+        for index in (0..current_index).rev() {
+            let ix = load_instruction_at_checked(index, instructions_sysvar)?;
+            if lending_tag(&ix) == Some(FLASH_BORROW_RESERVE_LIQUIDITY_TAG) {
+                borrow = Some((index, ix));
+                break;
+            }
+        }
+        let (borrow_index, borrow_ix) = borrow.ok_or(ArbitrageError::FlashLoanNotPaired)?;
+        require!(
+            borrow_ix.data[1..] == amount.to_le_bytes()
+                && account_at(&borrow_ix, 1) == Some(*user_token_account)
+                && account_at(&borrow_ix, 2) == Some(*reserve),
+            ArbitrageError::FlashLoanNotPaired
+        );
+
+        // First FlashRepay after us, which must point back at that borrow
+        let mut index = current_index + 1;
+        while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+            if lending_tag(&ix) == Some(FLASH_REPAY_RESERVE_LIQUIDITY_TAG) {
+                require!(
+                    ix.data.len() == 10
+                        && ix.data[1..9] == amount.to_le_bytes()
+                        && usize::from(ix.data[9]) == borrow_index
+                        && account_at(&ix, 0) == Some(*user_token_account)
+                        && account_at(&ix, 4) == Some(*reserve)
+                        && account_at(&ix, 6) == Some(*user_transfer_authority),
+                    ArbitrageError::FlashLoanNotPaired
+                );
+                return Ok(());
+            }
+            index += 1;
+        }
+        err!(ArbitrageError::FlashLoanNotPaired)
+    }
+
+    /// Tag of a Solend lending instruction, or `None` for any other program
+    fn lending_tag(ix: &Instruction) -> Option<u8> {
+        if ix.program_id != SOLEND_PROGRAM_ID {
+            return None;
+        }
+        ix.data.first().copied()
+    }
+
+    fn account_at(ix: &Instruction, index: usize) -> Option<Pubkey> {
+        ix.accounts.get(index).map(|meta| meta.pubkey)
+    }
+}
+
+/// Solend `FlashBorrowReserveLiquidity { liquidity_amount }`, placed by the bot as a
+/// top-level instruction ahead of `flash_loan_arbitrage`
+pub fn create_solend_flash_loan_instruction(
+    accounts: &solend::FlashLoanAccounts,
+    amount: u64,
+) -> Result<Instruction> {
+    let flash_loan_ix = Instruction {
+        program_id: SOLEND_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.reserve_liquidity_supply, false),
+            AccountMeta::new(accounts.user_token_account, false),
+            AccountMeta::new(accounts.reserve, false),
+            AccountMeta::new_readonly(accounts.lending_market, false),
+            AccountMeta::new_readonly(accounts.lending_market_authority, false),
+            AccountMeta::new_readonly(instructions_sysvar::ID, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+        ],
+        data: {
+            let mut data = vec![solend::FLASH_BORROW_RESERVE_LIQUIDITY_TAG];
+            data.extend_from_slice(&amount.to_le_bytes());
+            data
+        },
+    };
+    Ok(flash_loan_ix)
+}
+
+/// Solend `FlashRepayReserveLiquidity { liquidity_amount, borrow_instruction_index }`
+///
+/// Placed by the bot as a top-level instruction after `flash_loan_arbitrage`. `amount` is the
+/// borrowed principal; Solend adds its flash loan fee when repaying.
+pub fn create_solend_flash_loan_repay_instruction(
+    accounts: &solend::FlashLoanAccounts,
+    amount: u64,
+    borrow_instruction_index: u8,
+) -> Result<Instruction> {
+    let repay_ix = Instruction {
+        program_id: SOLEND_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.user_token_account, false),
+            AccountMeta::new(accounts.reserve_liquidity_supply, false),
+            AccountMeta::new(accounts.reserve_liquidity_fee_receiver, false),
+            AccountMeta::new(accounts.host_fee_receiver, false),
+            AccountMeta::new_readonly(accounts.reserve, false),
+            AccountMeta::new_readonly(accounts.lending_market, false),
+            AccountMeta::new_readonly(accounts.user_transfer_authority, true),
+            AccountMeta::new_readonly(instructions_sysvar::ID, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+        ],
+        data: {
+            let mut data = vec![solend::FLASH_REPAY_RESERVE_LIQUIDITY_TAG];
+            data.extend_from_slice(&amount.to_le_bytes());
+            data.push(borrow_instruction_index);
+            data
+        },
+    };
+    Ok(repay_ix)
+}
+
+/// Reject a transaction that lands after the last slot its opportunity was quoted for
+pub fn check_opportunity_deadline(max_slot: Option<u64>) -> Result<()> {
+    if let Some(max_slot) = max_slot {
+        let current_slot = Clock::get()?.slot;
+        require!(current_slot <= max_slot, ArbitrageError::OpportunityExpired);
+    }
+    Ok(())
+}
+
+/// Compare a realized PnL with a `min_profit` in i128, where neither side can wrap
+pub fn meets_min_profit(realized_pnl: i64, min_profit: u64) -> bool {
+    i128::from(realized_pnl) >= i128::from(min_profit)
+}
+
+/// Realized PnL is the change in the input-mint balance. A gain is taken net of the transfer
+/// fee a Token-2022 mint withholds when that profit is moved out; a loss is negative.
+pub fn calculate_realized_pnl(
+    balance_before: u64,
+    balance_after: u64,
+    input_mint: &AccountInfo,
+) -> Result<i64> {
+    let realized_pnl = if balance_after >= balance_before {
+        let gross_profit = balance_after - balance_before;
+        let transfer_fee = calculate_transfer_fee(input_mint, gross_profit)?;
+        i64::try_from(gross_profit - transfer_fee).map_err(|_| ArbitrageError::ArithmeticError)?
+    } else {
+        i64::try_from(balance_before - balance_after)
+            .map_err(|_| ArbitrageError::ArithmeticError)?
+            .checked_neg()
+            .ok_or(ArbitrageError::ArithmeticError)?
+    };
+
+    msg!("💰 Balance before: {} | after: {} | realized PnL: {}",
+         balance_before, balance_after, realized_pnl);
+    Ok(realized_pnl)
+}
+
+/// Land a trade that missed `min_profit` as a failure so the circuit breaker can count it, as
+/// long as its loss stays within `max_loss_per_trade`; a larger loss reverts the trade.
+/// Only reached when the caller passes `land_failed_trade`. Missed trades revert by default,
+/// and a reverted trade leaves no state behind, so it never counts toward the failure streak.
+/// A landed failure counts toward the trade totals like any other landed trade.
+pub fn record_failed_trade(
+    arbitrage_state: &mut Account<ArbitrageState>,
+    user: Pubkey,
+    realized_pnl: i64,
+    min_profit: u64,
+    current_time: i64,
+) -> Result<()> {
+    let max_loss = i64::try_from(arbitrage_state.max_loss_per_trade)
+        .map_err(|_| ArbitrageError::ArithmeticError)?;
+    require!(realized_pnl >= -max_loss, ArbitrageError::InsufficientProfit);
+
+    arbitrage_state.last_execution_time = current_time;
+    arbitrage_state.record_trade_totals(realized_pnl)?;
+    let tripped = arbitrage_state.record_trade_result(realized_pnl, false, current_time)?;
+
+    emit!(ArbitrageFailed {
+        user,
+        pnl: realized_pnl,
+        min_profit,
+        consecutive_failures: arbitrage_state.consecutive_failures,
+        timestamp: current_time,
+    });
+    msg!("⚠️ Trade missed its minimum profit (PnL: {}, streak: {})",
+         realized_pnl, arbitrage_state.consecutive_failures);
+
+    if let Some(reason) = tripped {
+        emit_circuit_breaker_tripped(arbitrage_state, reason, current_time);
+    }
+    Ok(())
+}
+
+pub fn emit_circuit_breaker_tripped(
+    arbitrage_state: &Account<ArbitrageState>,
+    reason: CircuitBreakerReason,
+    current_time: i64,
+) {
+    emit!(CircuitBreakerTripped {
+        arbitrage_state: arbitrage_state.key(),
+        reason,
+        daily_pnl: arbitrage_state.daily_pnl,
+        consecutive_failures: arbitrage_state.consecutive_failures,
+        timestamp: current_time,
+    });
+    msg!("🛑 Circuit breaker tripped: {:?}, bot paused", reason);
+}
+
+/// Move everything the user account gained since `balance_before` into the profit vault.
+/// With a Token-2022 transfer fee the vault receives exactly the realized profit.
+pub fn sweep_profit_to_vault<'info>(
+    user_token_account: &InterfaceAccount<'info, TokenAccount>,
+    profit_vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    user: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    balance_before: u64,
+) -> Result<()> {
+    let gross_profit = user_token_account
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(ArbitrageError::ArithmeticError)?;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: user_token_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: profit_vault.to_account_info(),
+                authority: user.to_account_info(),
+            },
+        ),
+        gross_profit,
+        mint.decimals,
+    )?;
+
+    msg!("🏦 Swept {} tokens into profit vault {}", gross_profit, profit_vault.key());
+    Ok(())
+}
+
+/// Fee withheld by the Token-2022 transfer-fee extension when transferring `amount` of `mint`
+pub fn calculate_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != TOKEN_2022_PROGRAM_ID {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| ArbitrageError::ArithmeticError.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Fee Solend charges on a flash loan of `amount`, decoded from the reserve's fee config
+pub fn calculate_flash_loan_fee(reserve: &AccountInfo, amount: u64) -> Result<u64> {
+    solend::read_reserve_fees(reserve)?.flash_loan_fee(amount)
+}
+
+// Convenience functions for OrcaSwap
 impl<'info> OrcaSwap<'info> {
     pub fn validate_accounts(&self) -> Result<()> {
         // Basic validation - more can be added
@@ -853,6 +4797,1332 @@ impl<'info> OrcaSwap<'info> {
             !self.token_owner_account_b.key().eq(&Pubkey::default()),
             ArbitrageError::AccountValidationFailed
         );
+
+        let (mint_a, mint_b) = whirlpool_swap::read_pool_mints(&self.whirlpool)?;
+        let arbitrage_state = self.arbitrage_state.key();
+        require!(self.allowed_mint_a.allows(&arbitrage_state, &mint_a), ArbitrageError::MintNotAllowed);
+        require!(self.allowed_mint_b.allows(&arbitrage_state, &mint_b), ArbitrageError::MintNotAllowed);
+        require!(self.mint_stats_a.is_for(&arbitrage_state, &mint_a), ArbitrageError::AccountValidationFailed);
+        require!(self.mint_stats_b.is_for(&arbitrage_state, &mint_b), ArbitrageError::AccountValidationFailed);
+        Ok(())
+    }
+}
+
+// Convenience functions for OrcaSwapV2
+impl<'info> OrcaSwapV2<'info> {
+    pub fn validate_accounts(&self) -> Result<()> {
+        let (mint_a, mint_b) = whirlpool_swap::read_pool_mints(&self.whirlpool)?;
+        require_keys_eq!(self.token_mint_a.key(), mint_a, ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(self.token_mint_b.key(), mint_b, ArbitrageError::AccountValidationFailed);
+        require_keys_neq!(
+            self.token_owner_account_a.key(),
+            self.token_owner_account_b.key(),
+            ArbitrageError::InvalidSwapPair
+        );
+        Ok(())
+    }
+}
+
+// Convenience functions for OrcaTwoHopSwap
+impl<'info> OrcaTwoHopSwap<'info> {
+    pub fn validate_accounts(&self, a_to_b_one: bool, a_to_b_two: bool) -> Result<()> {
+        let (one_a, one_b) = whirlpool_swap::read_pool_mints(&self.whirlpool_one)?;
+        let (two_a, two_b) = whirlpool_swap::read_pool_mints(&self.whirlpool_two)?;
+
+        let (input, intermediate_one) = if a_to_b_one { (one_a, one_b) } else { (one_b, one_a) };
+        let (intermediate_two, output) = if a_to_b_two { (two_a, two_b) } else { (two_b, two_a) };
+
+        require_keys_eq!(intermediate_one, intermediate_two, ArbitrageError::InvalidSwapPair);
+        require_keys_eq!(self.token_mint_input.key(), input, ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(self.token_mint_intermediate.key(), intermediate_one, ArbitrageError::AccountValidationFailed);
+        require_keys_eq!(self.token_mint_output.key(), output, ArbitrageError::AccountValidationFailed);
+        Ok(())
+    }
+}
+
+// Convenience functions for RaydiumSwap
+impl<'info> RaydiumSwap<'info> {
+    pub fn validate_accounts(&self) -> Result<()> {
+        require_keys_eq!(*self.amm.owner, RAYDIUM_AMM_PROGRAM_ID, ArbitrageError::AccountValidationFailed);
+        require_keys_neq!(
+            self.user_source_token_account.key(),
+            self.user_destination_token_account.key(),
+            ArbitrageError::InvalidSwapPair
+        );
+
+        let arbitrage_state = self.arbitrage_state.key();
+        let source_mint = token_account_mint(&self.user_source_token_account)?;
+        let destination_mint = token_account_mint(&self.user_destination_token_account)?;
+        require!(self.allowed_source_mint.allows(&arbitrage_state, &source_mint), ArbitrageError::MintNotAllowed);
+        require!(
+            self.allowed_destination_mint.allows(&arbitrage_state, &destination_mint),
+            ArbitrageError::MintNotAllowed
+        );
+        require!(
+            self.mint_stats_source.is_for(&arbitrage_state, &source_mint),
+            ArbitrageError::AccountValidationFailed
+        );
+        require!(
+            self.mint_stats_destination.is_for(&arbitrage_state, &destination_mint),
+            ArbitrageError::AccountValidationFailed
+        );
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::InstructionData;
+    use instructions_sysvar::{BorrowedAccountMeta, BorrowedInstruction};
+
+    /// Local stand-in for the Solend lending program: decodes flash loan instructions the same
+    /// way Solend's `LendingInstruction::unpack` does and checks the account list it expects.
+    mod mock_lending {
+        use super::*;
+
+        #[derive(Debug, PartialEq)]
+        pub enum FlashLoanInstruction {
+            FlashBorrowReserveLiquidity { liquidity_amount: u64 },
+            FlashRepayReserveLiquidity { liquidity_amount: u64, borrow_instruction_index: u8 },
+        }
+
+        pub fn process(ix: &Instruction) -> FlashLoanInstruction {
+            assert_eq!(ix.program_id, SOLEND_PROGRAM_ID);
+            let (tag, rest) = ix.data.split_first().expect("empty instruction data");
+            match *tag {
+                19 => {
+                    assert_eq!(rest.len(), 8);
+                    assert_eq!(ix.accounts.len(), 7);
+                    FlashLoanInstruction::FlashBorrowReserveLiquidity {
+                        liquidity_amount: u64::from_le_bytes(rest.try_into().unwrap()),
+                    }
+                }
+                20 => {
+                    assert_eq!(rest.len(), 9);
+                    assert_eq!(ix.accounts.len(), 9);
+                    FlashLoanInstruction::FlashRepayReserveLiquidity {
+                        liquidity_amount: u64::from_le_bytes(rest[..8].try_into().unwrap()),
+                        borrow_instruction_index: rest[8],
+                    }
+                }
+                other => panic!("unexpected lending instruction tag {}", other),
+            }
+        }
+    }
+
+    fn flash_loan_accounts() -> solend::FlashLoanAccounts {
+        solend::FlashLoanAccounts {
+            reserve: Pubkey::new_unique(),
+            reserve_liquidity_supply: Pubkey::new_unique(),
+            reserve_liquidity_fee_receiver: Pubkey::new_unique(),
+            host_fee_receiver: Pubkey::new_unique(),
+            lending_market: Pubkey::new_unique(),
+            lending_market_authority: Pubkey::new_unique(),
+            user_token_account: Pubkey::new_unique(),
+            user_transfer_authority: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM_ID,
+        }
+    }
+
+    #[test]
+    fn solend_flash_borrow_encoding() {
+        let accounts = flash_loan_accounts();
+        let ix = create_solend_flash_loan_instruction(&accounts, 1_000_000).unwrap();
+
+        assert_eq!(ix.data, vec![19, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(accounts.reserve_liquidity_supply, false),
+                AccountMeta::new(accounts.user_token_account, false),
+                AccountMeta::new(accounts.reserve, false),
+                AccountMeta::new_readonly(accounts.lending_market, false),
+                AccountMeta::new_readonly(accounts.lending_market_authority, false),
+                AccountMeta::new_readonly(instructions_sysvar::ID, false),
+                AccountMeta::new_readonly(accounts.token_program, false),
+            ]
+        );
+        assert_eq!(
+            mock_lending::process(&ix),
+            mock_lending::FlashLoanInstruction::FlashBorrowReserveLiquidity { liquidity_amount: 1_000_000 }
+        );
+    }
+
+    #[test]
+    fn solend_flash_repay_encoding() {
+        let accounts = flash_loan_accounts();
+        let ix = create_solend_flash_loan_repay_instruction(&accounts, u64::MAX - 1, 3).unwrap();
+
+        assert_eq!(ix.data, vec![20, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03]);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(accounts.user_token_account, false),
+                AccountMeta::new(accounts.reserve_liquidity_supply, false),
+                AccountMeta::new(accounts.reserve_liquidity_fee_receiver, false),
+                AccountMeta::new(accounts.host_fee_receiver, false),
+                AccountMeta::new_readonly(accounts.reserve, false),
+                AccountMeta::new_readonly(accounts.lending_market, false),
+                AccountMeta::new_readonly(accounts.user_transfer_authority, true),
+                AccountMeta::new_readonly(instructions_sysvar::ID, false),
+                AccountMeta::new_readonly(accounts.token_program, false),
+            ]
+        );
+        assert_eq!(
+            mock_lending::process(&ix),
+            mock_lending::FlashLoanInstruction::FlashRepayReserveLiquidity {
+                liquidity_amount: u64::MAX - 1,
+                borrow_instruction_index: 3,
+            }
+        );
+    }
+
+    /// Serialize `instructions` the way the runtime fills the instructions sysvar, with
+    /// `current` as the executing instruction's index
+    fn instructions_sysvar_data(instructions: &[Instruction], current: u16) -> Vec<u8> {
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|ix| BorrowedInstruction {
+                program_id: &ix.program_id,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|meta| BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: &ix.data,
+            })
+            .collect();
+        let mut data = instructions_sysvar::construct_instructions_data(&borrowed);
+        // The runtime keeps the executing instruction's index in the trailing two bytes
+        let len = data.len();
+        data[len - 2..].copy_from_slice(&current.to_le_bytes());
+        data
+    }
+
+    fn verify_pairing(
+        instructions: &[Instruction],
+        current: u16,
+        accounts: &solend::FlashLoanAccounts,
+        amount: u64,
+    ) -> Result<()> {
+        let key = instructions_sysvar::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let mut lamports = 0;
+        let mut data = instructions_sysvar_data(instructions, current);
+        let sysvar = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        solend::verify_flash_loan_pairing(
+            &sysvar,
+            &accounts.reserve,
+            &accounts.user_token_account,
+            &accounts.user_transfer_authority,
+            amount,
+        )
+    }
+
+    #[test]
+    fn flash_loan_pairing_through_instructions_sysvar() {
+        let accounts = flash_loan_accounts();
+        let amount = 1_000_000;
+        let compute_budget = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![2, 0x40, 0x0d, 0x03, 0x00],
+        };
+        let arbitrage = Instruction {
+            program_id: crate::ID,
+            accounts: vec![AccountMeta::new_readonly(instructions_sysvar::ID, false)],
+            data: crate::instruction::FlashLoanArbitrage {
+                flash_loan_amount: amount,
+                routes: vec![],
+                min_profit: 1,
+                amount_mode: RouteAmountMode::Fixed,
+                max_slot: None,
+                land_failed_trade: false,
+            }
+            .data(),
+        };
+        let borrow = create_solend_flash_loan_instruction(&accounts, amount).unwrap();
+        let repay = create_solend_flash_loan_repay_instruction(&accounts, amount, 1).unwrap();
+
+        // [compute budget, FlashBorrow, flash_loan_arbitrage, FlashRepay(borrow index 1)]
+        let layout = vec![compute_budget.clone(), borrow.clone(), arbitrage.clone(), repay.clone()];
+        assert!(verify_pairing(&layout, 2, &accounts, amount).is_ok());
+
+        let not_paired = |instructions: &[Instruction], current: u16, amount: u64| {
+            assert_eq!(
+                verify_pairing(instructions, current, &accounts, amount).unwrap_err(),
+                ArbitrageError::FlashLoanNotPaired.into()
+            );
+        };
+
+        // Executing instruction is not ours (e.g. we were reached through another program)
+        not_paired(&layout, 0, amount);
+        // Borrowed amount differs from the one being traded
+        not_paired(&layout, 2, amount + 1);
+        // No repay after us, or no borrow before us
+        not_paired(&layout[..3], 2, amount);
+        not_paired(&[compute_budget.clone(), arbitrage.clone(), repay.clone()], 1, amount);
+        // Repay names a different borrow index
+        let stray_repay = create_solend_flash_loan_repay_instruction(&accounts, amount, 0).unwrap();
+        not_paired(&[compute_budget.clone(), borrow.clone(), arbitrage.clone(), stray_repay], 2, amount);
+        // Repay is signed for by someone other than the trader
+        let other_authority = solend::FlashLoanAccounts {
+            reserve: accounts.reserve,
+            reserve_liquidity_supply: accounts.reserve_liquidity_supply,
+            reserve_liquidity_fee_receiver: accounts.reserve_liquidity_fee_receiver,
+            host_fee_receiver: accounts.host_fee_receiver,
+            lending_market: accounts.lending_market,
+            lending_market_authority: accounts.lending_market_authority,
+            user_token_account: accounts.user_token_account,
+            user_transfer_authority: Pubkey::new_unique(),
+            token_program: accounts.token_program,
+        };
+        let foreign_repay = create_solend_flash_loan_repay_instruction(&other_authority, amount, 1).unwrap();
+        not_paired(&[compute_budget, borrow, arbitrage, foreign_repay], 2, amount);
+    }
+
+    #[test]
+    fn legacy_state_migration() {
+        let authority = Pubkey::new_unique();
+        let mut legacy = Vec::with_capacity(ArbitrageState::LEGACY_LEN);
+        legacy.extend_from_slice(ArbitrageState::DISCRIMINATOR);
+        legacy.extend_from_slice(authority.as_ref());
+        legacy.push(1);
+        legacy.extend_from_slice(&300i64.to_le_bytes());
+        legacy.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        legacy.extend_from_slice(&42u64.to_le_bytes());
+        legacy.extend_from_slice(&9_000u64.to_le_bytes());
+        legacy.push(254);
+        assert_eq!(legacy.len(), ArbitrageState::LEGACY_LEN);
+
+        let state = ArbitrageState::from_legacy(&legacy).unwrap();
+        assert_eq!(state.version, ArbitrageState::CURRENT_VERSION);
+        assert_eq!(state.state_id, authority);
+        assert_eq!(state.authority, authority);
+        assert!(state.is_paused);
+        assert_eq!(state.min_execution_interval, 300);
+        assert_eq!(state.last_execution_time, 1_700_000_000);
+        assert_eq!(state.total_trades, 42);
+        assert_eq!(state.total_profit, 9_000);
+        assert_eq!(state.bump, 254);
+        assert_eq!(state.max_hops, ArbitrageState::DEFAULT_MAX_HOPS);
+        assert_eq!(state.config_timelock, ArbitrageState::DEFAULT_CONFIG_TIMELOCK);
+
+        // A fully populated state must fit the space allocated for it
+        let mut full = state.clone();
+        full.pending_authority = Some(authority);
+        full.guardian = Some(authority);
+        full.operators = vec![authority; ArbitrageState::MAX_OPERATORS];
+        full.mint_notional_limits = vec![
+            MintNotionalLimit { mint: authority, max_amount_in: 1 };
+            ArbitrageState::MAX_MINT_NOTIONAL_LIMITS
+        ];
+        let mut data = Vec::new();
+        full.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ArbitrageState::LEN);
+    }
+
+    fn route(input_mint: Pubkey, output_mint: Pubkey) -> SwapRoute {
+        SwapRoute {
+            dex_id: DexId::Orca,
+            input_mint,
+            output_mint,
+            amount_in: 1_000,
+            min_amount_out: 0,
+            jupiter_route: None,
+        }
+    }
+
+    fn route_error(routes: &[SwapRoute]) -> Error {
+        validate_route_chain(routes).unwrap_err()
+    }
+
+    fn limited_state(usdc: Pubkey) -> ArbitrageState {
+        let mut state = ArbitrageState::new(Pubkey::new_unique(), Pubkey::new_unique(), 60, 255);
+        state.min_profit_bps = 50;
+        state.mint_notional_limits.push(MintNotionalLimit { mint: usdc, max_amount_in: 1_000 });
+        state.max_loss_per_trade = 100;
+        state.max_daily_loss = 1_000;
+        state.max_consecutive_failures = 3;
+        state
+    }
+
+    #[test]
+    fn config_change_loosening_per_field() {
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let state = limited_state(usdc);
+        let limit = |mint, max_amount_in| Some(MintNotionalLimit { mint, max_amount_in });
+
+        let cases = vec![
+            ("shorter interval", ConfigChange { min_execution_interval: Some(30), ..Default::default() }, true),
+            ("same interval", ConfigChange { min_execution_interval: Some(60), ..Default::default() }, false),
+            ("longer interval", ConfigChange { min_execution_interval: Some(120), ..Default::default() }, false),
+            ("shorter timelock", ConfigChange { config_timelock: Some(3_600), ..Default::default() }, true),
+            ("longer timelock", ConfigChange { config_timelock: Some(2 * SECONDS_PER_DAY), ..Default::default() }, false),
+            ("more hops", ConfigChange { max_hops: Some(5), ..Default::default() }, true),
+            ("fewer hops", ConfigChange { max_hops: Some(3), ..Default::default() }, false),
+            ("lower min profit bps", ConfigChange { min_profit_bps: Some(10), ..Default::default() }, true),
+            ("higher min profit bps", ConfigChange { min_profit_bps: Some(100), ..Default::default() }, false),
+            ("raised notional cap", ConfigChange { mint_notional_limit: limit(usdc, 2_000), ..Default::default() }, true),
+            ("lowered notional cap", ConfigChange { mint_notional_limit: limit(usdc, 500), ..Default::default() }, false),
+            ("removed notional cap", ConfigChange { mint_notional_limit: limit(usdc, u64::MAX), ..Default::default() }, true),
+            ("new cap on uncapped mint", ConfigChange { mint_notional_limit: limit(sol, 500), ..Default::default() }, false),
+            ("higher max loss per trade", ConfigChange { max_loss_per_trade: Some(200), ..Default::default() }, true),
+            ("lower max loss per trade", ConfigChange { max_loss_per_trade: Some(50), ..Default::default() }, false),
+            ("higher daily loss limit", ConfigChange { max_daily_loss: Some(2_000), ..Default::default() }, true),
+            ("lower daily loss limit", ConfigChange { max_daily_loss: Some(500), ..Default::default() }, false),
+            ("disabled daily loss limit", ConfigChange { max_daily_loss: Some(0), ..Default::default() }, true),
+            ("longer failure streak", ConfigChange { max_consecutive_failures: Some(5), ..Default::default() }, true),
+            ("shorter failure streak", ConfigChange { max_consecutive_failures: Some(2), ..Default::default() }, false),
+            ("disabled failure streak", ConfigChange { max_consecutive_failures: Some(0), ..Default::default() }, true),
+            ("enabled aggregator legs", ConfigChange { allow_aggregator_legs: Some(true), ..Default::default() }, true),
+            ("disabled aggregator legs", ConfigChange { allow_aggregator_legs: Some(false), ..Default::default() }, false),
+        ];
+        for (name, change, loosens) in cases {
+            assert_eq!(state.is_loosened_by(&change), loosens, "{}", name);
+        }
+
+        // Enabling a breaker that is currently off only tightens
+        let mut unlimited = limited_state(usdc);
+        unlimited.max_daily_loss = 0;
+        unlimited.max_consecutive_failures = 0;
+        assert!(!unlimited.is_loosened_by(&ConfigChange { max_daily_loss: Some(500), ..Default::default() }));
+        assert!(!unlimited.is_loosened_by(&ConfigChange { max_consecutive_failures: Some(2), ..Default::default() }));
+    }
+
+    #[test]
+    fn trade_results_roll_daily_pnl_at_utc_midnight() {
+        let mut state = ArbitrageState::new(Pubkey::new_unique(), Pubkey::new_unique(), 60, 255);
+        let day_start = 20_000 * SECONDS_PER_DAY;
+
+        assert_eq!(state.record_trade_result(500, true, day_start + 10).unwrap(), None);
+        assert_eq!(state.record_trade_result(-200, false, day_start + SECONDS_PER_DAY - 1).unwrap(), None);
+        assert_eq!((state.pnl_day, state.daily_pnl), (20_000, 300));
+
+        assert_eq!(state.record_trade_result(-50, false, day_start + SECONDS_PER_DAY).unwrap(), None);
+        assert_eq!((state.pnl_day, state.daily_pnl), (20_001, -50));
+        assert!(!state.is_paused);
+    }
+
+    #[test]
+    fn trade_results_trip_daily_loss_limit() {
+        let mut state = ArbitrageState::new(Pubkey::new_unique(), Pubkey::new_unique(), 60, 255);
+        state.max_daily_loss = 100;
+        let now = 20_000 * SECONDS_PER_DAY;
+
+        assert_eq!(state.record_trade_result(-60, false, now).unwrap(), None);
+        assert!(!state.is_paused);
+        assert_eq!(
+            state.record_trade_result(-40, false, now + 1).unwrap(),
+            Some(CircuitBreakerReason::DailyLossLimit)
+        );
+        assert!(state.is_paused);
+
+        // A fresh day starts from zero
+        state.is_paused = false;
+        assert_eq!(state.record_trade_result(-99, false, now + SECONDS_PER_DAY).unwrap(), None);
+        assert!(!state.is_paused);
+    }
+
+    #[test]
+    fn trade_results_trip_and_reset_failure_streak() {
+        let mut state = ArbitrageState::new(Pubkey::new_unique(), Pubkey::new_unique(), 60, 255);
+        state.max_consecutive_failures = 3;
+        let now = 20_000 * SECONDS_PER_DAY;
+
+        state.record_trade_result(-1, false, now).unwrap();
+        state.record_trade_result(-1, false, now).unwrap();
+        assert_eq!(state.consecutive_failures, 2);
+
+        // A success resets the streak
+        assert_eq!(state.record_trade_result(10, true, now).unwrap(), None);
+        assert_eq!(state.consecutive_failures, 0);
+
+        state.record_trade_result(-1, false, now).unwrap();
+        state.record_trade_result(-1, false, now).unwrap();
+        assert!(!state.is_paused);
+        assert_eq!(
+            state.record_trade_result(-1, false, now).unwrap(),
+            Some(CircuitBreakerReason::FailureStreak)
+        );
+        assert_eq!(state.consecutive_failures, 3);
+        assert!(state.is_paused);
+    }
+
+    #[test]
+    fn threshold_loosening() {
+        for (current, new, loosens) in [
+            (0, 0, false),
+            (0, 5, false),
+            (5, 0, true),
+            (5, 6, true),
+            (5, 5, false),
+            (5, 4, false),
+        ] {
+            assert_eq!(loosens_threshold(current, new), loosens, "{} -> {}", current, new);
+        }
+    }
+
+    #[test]
+    fn config_change_bounds_timelock() {
+        let timelock = |timelock| ConfigChange { config_timelock: Some(timelock), ..Default::default() };
+
+        assert!(timelock(0).validate().is_ok());
+        assert!(timelock(ArbitrageState::MAX_CONFIG_TIMELOCK).validate().is_ok());
+        for invalid in [-1, ArbitrageState::MAX_CONFIG_TIMELOCK + 1, i64::MAX] {
+            assert_eq!(
+                timelock(invalid).validate().unwrap_err(),
+                ArbitrageError::InvalidConfigChange.into()
+            );
+        }
+        assert_eq!(
+            ConfigChange::default().validate().unwrap_err(),
+            ArbitrageError::InvalidConfigChange.into()
+        );
+    }
+
+    #[test]
+    fn route_chain_accepts_closed_cycles() {
+        let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert!(validate_route_chain(&[route(usdc, sol), route(sol, usdc)]).is_ok());
+        assert!(validate_route_chain(&[route(usdc, sol), route(sol, bonk), route(bonk, usdc)]).is_ok());
+    }
+
+    #[test]
+    fn route_chain_rejects_invalid_sequences() {
+        let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(route_error(&[]), ArbitrageError::EmptyRoutes.into());
+        assert_eq!(
+            route_error(&[route(usdc, usdc)]),
+            ArbitrageError::InvalidSwapPair.into()
+        );
+        assert_eq!(
+            route_error(&[route(usdc, sol), route(sol, sol), route(sol, usdc)]),
+            ArbitrageError::InvalidSwapPair.into()
+        );
+        assert_eq!(
+            route_error(&[route(usdc, sol), route(bonk, usdc)]),
+            ArbitrageError::BrokenRouteChain.into()
+        );
+        assert_eq!(
+            route_error(&[route(usdc, sol), route(sol, bonk)]),
+            ArbitrageError::RouteNotClosed.into()
+        );
+        assert_eq!(route_error(&[route(usdc, sol)]), ArbitrageError::RouteNotClosed.into());
+    }
+
+    #[test]
+    fn route_plan_only_counts_on_jupiter_legs() {
+        let (usdc, sol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let plan = JupiterRoutePlan { shared_accounts_id: None, route_plan: vec![], account_count: 9 };
+
+        let mut jupiter_leg = route(usdc, sol);
+        jupiter_leg.dex_id = DexId::Jupiter;
+        jupiter_leg.jupiter_route = Some(plan.clone());
+        assert_eq!(jupiter_leg.hop_account_count(), 3 + 9);
+
+        let mut orca_leg = route(sol, usdc);
+        orca_leg.jupiter_route = Some(plan);
+        assert_eq!(orca_leg.hop_account_count(), 15);
+        assert_eq!(
+            route_error(&[jupiter_leg.clone(), orca_leg]),
+            ArbitrageError::UnexpectedRoutePlan.into()
+        );
+        assert!(validate_route_chain(&[jupiter_leg, route(sol, usdc)]).is_ok());
+    }
+
+    #[test]
+    fn route_endpoints_bind_to_user_token_account() {
+        let user_token_account = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let program = Pubkey::default();
+        let keys = [Pubkey::new_unique(), user_token_account, other, user_token_account];
+        let mut lamports = [0u64; 4];
+        let mut data: [Vec<u8>; 4] = Default::default();
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| AccountInfo::new(key, false, true, lamports, data, &program, false, 0))
+            .collect();
+        // [program, source, destination] for each hop shape
+        let from_user = [infos[0].clone(), infos[1].clone(), infos[2].clone()];
+        let to_user = [infos[0].clone(), infos[2].clone(), infos[3].clone()];
+        let round_trip = [infos[0].clone(), infos[1].clone(), infos[3].clone()];
+        let elsewhere = [infos[0].clone(), infos[2].clone(), infos[2].clone()];
+
+        assert!(check_route_endpoint(0, 1, &round_trip, &user_token_account).is_ok());
+        assert!(check_route_endpoint(0, 2, &from_user, &user_token_account).is_ok());
+        assert!(check_route_endpoint(1, 2, &to_user, &user_token_account).is_ok());
+        assert!(check_route_endpoint(1, 3, &elsewhere, &user_token_account).is_ok());
+
+        assert_eq!(
+            check_route_endpoint(0, 2, &elsewhere, &user_token_account).unwrap_err(),
+            ArbitrageError::RouteEndpointMismatch.into()
+        );
+        assert_eq!(
+            check_route_endpoint(1, 2, &elsewhere, &user_token_account).unwrap_err(),
+            ArbitrageError::RouteEndpointMismatch.into()
+        );
+        assert_eq!(
+            check_route_endpoint(0, 1, &from_user, &user_token_account).unwrap_err(),
+            ArbitrageError::RouteEndpointMismatch.into()
+        );
+    }
+
+    fn reserve_data(flash_loan_fee_wad: u64, host_fee_percentage: u8) -> Vec<u8> {
+        let mut data = vec![0u8; solend::RESERVE_LEN];
+        data[0] = solend::RESERVE_VERSION;
+        data[314..322].copy_from_slice(&flash_loan_fee_wad.to_le_bytes());
+        data[322] = host_fee_percentage;
+        data
+    }
+
+    #[test]
+    fn flash_loan_fee_from_reserve_config() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        // 0.3% with a 20% host cut
+        let mut data = reserve_data(3_000_000_000_000_000, 20);
+        let reserve = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &SOLEND_PROGRAM_ID, false, 0);
+        assert_eq!(calculate_flash_loan_fee(&reserve, 1_000_000).unwrap(), 3_000);
+        // 0.3% of 100 is 0.3, raised to the two-token minimum a host fee requires
+        assert_eq!(calculate_flash_loan_fee(&reserve, 100).unwrap(), 2);
+        // The fee can never swallow the loan
+        assert_eq!(
+            calculate_flash_loan_fee(&reserve, 2).unwrap_err(),
+            ArbitrageError::InvalidAmount.into()
+        );
+
+        let fees = |flash_loan_fee_wad, host_fee_percentage| solend::ReserveFees {
+            flash_loan_fee_wad,
+            host_fee_percentage,
+        };
+        // Half-up rounding: 5 * 0.3 = 1.5 -> 2, 3 * 0.3 = 0.9 -> 1 (also the minimum without a host fee)
+        let thirty_percent = 300_000_000_000_000_000;
+        assert_eq!(fees(thirty_percent, 0).flash_loan_fee(5).unwrap(), 2);
+        assert_eq!(fees(thirty_percent, 0).flash_loan_fee(3).unwrap(), 1);
+        assert_eq!(fees(thirty_percent, 0).flash_loan_fee(4).unwrap(), 1);
+        // Free reserves and empty loans cost nothing
+        assert_eq!(fees(0, 20).flash_loan_fee(1_000_000).unwrap(), 0);
+        assert_eq!(fees(thirty_percent, 20).flash_loan_fee(0).unwrap(), 0);
+        // Amounts whose scaled product exceeds u64 stay exact in u128
+        assert_eq!(
+            fees(1_000_000_000_000_000, 0).flash_loan_fee(u64::MAX).unwrap(),
+            18_446_744_073_709_552
+        );
+        // A fee rate above 100% overflows u64 instead of wrapping
+        assert_eq!(
+            fees(u64::MAX, 0).flash_loan_fee(u64::MAX).unwrap_err(),
+            ArbitrageError::ArithmeticError.into()
+        );
+    }
+
+    #[test]
+    fn flash_loan_fee_rejects_foreign_reserves() {
+        let key = Pubkey::new_unique();
+        let impostor = Pubkey::new_unique();
+        let (mut lamports, mut other_lamports, mut short_lamports) = (0, 0, 0);
+        let mut data = reserve_data(0, 0);
+        let mut other_data = reserve_data(0, 0);
+        let mut short_data = vec![solend::RESERVE_VERSION; 322];
+
+        let foreign = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &impostor, false, 0);
+        assert_eq!(
+            calculate_flash_loan_fee(&foreign, 1_000).unwrap_err(),
+            ArbitrageError::InvalidFlashLoanReserve.into()
+        );
+        other_data[0] = 0;
+        let unversioned =
+            AccountInfo::new(&key, false, false, &mut other_lamports, &mut other_data, &SOLEND_PROGRAM_ID, false, 0);
+        assert_eq!(
+            calculate_flash_loan_fee(&unversioned, 1_000).unwrap_err(),
+            ArbitrageError::InvalidFlashLoanReserve.into()
+        );
+        let short =
+            AccountInfo::new(&key, false, false, &mut short_lamports, &mut short_data, &SOLEND_PROGRAM_ID, false, 0);
+        assert_eq!(
+            calculate_flash_loan_fee(&short, 1_000).unwrap_err(),
+            ArbitrageError::InvalidFlashLoanReserve.into()
+        );
+    }
+
+    #[test]
+    fn raydium_amm_swap_base_in_layout() {
+        let keys = raydium_amm::SwapBaseInKeys {
+            token_program: TOKEN_PROGRAM_ID,
+            amm: Pubkey::new_unique(),
+            amm_authority: Pubkey::new_unique(),
+            amm_open_orders: Pubkey::new_unique(),
+            amm_target_orders: Pubkey::new_unique(),
+            pool_coin_token_account: Pubkey::new_unique(),
+            pool_pc_token_account: Pubkey::new_unique(),
+            serum_program: Pubkey::new_unique(),
+            serum_market: Pubkey::new_unique(),
+            serum_bids: Pubkey::new_unique(),
+            serum_asks: Pubkey::new_unique(),
+            serum_event_queue: Pubkey::new_unique(),
+            serum_coin_vault: Pubkey::new_unique(),
+            serum_pc_vault: Pubkey::new_unique(),
+            serum_vault_signer: Pubkey::new_unique(),
+            user_source_token_account: Pubkey::new_unique(),
+            user_destination_token_account: Pubkey::new_unique(),
+            user_owner: Pubkey::new_unique(),
+        };
+        let ix = raydium_amm::swap_base_in_instruction(&keys, 1_000_000, 0x0102_0304_0506_0708);
+
+        assert_eq!(ix.program_id, RAYDIUM_AMM_PROGRAM_ID);
+        assert_eq!(
+            ix.data,
+            vec![9, 0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]
+        );
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new_readonly(keys.token_program, false),
+                AccountMeta::new(keys.amm, false),
+                AccountMeta::new_readonly(keys.amm_authority, false),
+                AccountMeta::new(keys.amm_open_orders, false),
+                AccountMeta::new(keys.amm_target_orders, false),
+                AccountMeta::new(keys.pool_coin_token_account, false),
+                AccountMeta::new(keys.pool_pc_token_account, false),
+                AccountMeta::new_readonly(keys.serum_program, false),
+                AccountMeta::new(keys.serum_market, false),
+                AccountMeta::new(keys.serum_bids, false),
+                AccountMeta::new(keys.serum_asks, false),
+                AccountMeta::new(keys.serum_event_queue, false),
+                AccountMeta::new(keys.serum_coin_vault, false),
+                AccountMeta::new(keys.serum_pc_vault, false),
+                AccountMeta::new_readonly(keys.serum_vault_signer, false),
+                AccountMeta::new(keys.user_source_token_account, false),
+                AccountMeta::new(keys.user_destination_token_account, false),
+                AccountMeta::new_readonly(keys.user_owner, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn raydium_clmm_swap_v2_layout() {
+        let keys = raydium_clmm::SwapV2Keys {
+            payer: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            pool_state: Pubkey::new_unique(),
+            input_token_account: Pubkey::new_unique(),
+            output_token_account: Pubkey::new_unique(),
+            input_vault: Pubkey::new_unique(),
+            output_vault: Pubkey::new_unique(),
+            observation_state: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM_ID,
+            token_program_2022: TOKEN_2022_PROGRAM_ID,
+            memo_program: Pubkey::new_unique(),
+            input_vault_mint: Pubkey::new_unique(),
+            output_vault_mint: Pubkey::new_unique(),
+            tick_array_bitmap_extension: Pubkey::new_unique(),
+            tick_arrays: vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let args = raydium_clmm::SwapV2Instruction {
+            amount: 1_000_000,
+            other_amount_threshold: 0x0102_0304_0506_0708,
+            sqrt_price_limit_x64: 0,
+            is_base_input: true,
+        };
+        let ix = raydium_clmm::swap_v2_instruction(&keys, &args).unwrap();
+
+        assert_eq!(ix.program_id, RAYDIUM_CLMM_PROGRAM_ID);
+        let mut expected_data = vec![0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+        expected_data.extend_from_slice(&[0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        expected_data.extend_from_slice(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        expected_data.extend_from_slice(&[0; 16]);
+        expected_data.push(1);
+        assert_eq!(ix.data, expected_data);
+
+        let mut expected_accounts = vec![
+            AccountMeta::new_readonly(keys.payer, true),
+            AccountMeta::new_readonly(keys.amm_config, false),
+            AccountMeta::new(keys.pool_state, false),
+            AccountMeta::new(keys.input_token_account, false),
+            AccountMeta::new(keys.output_token_account, false),
+            AccountMeta::new(keys.input_vault, false),
+            AccountMeta::new(keys.output_vault, false),
+            AccountMeta::new(keys.observation_state, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+            AccountMeta::new_readonly(keys.memo_program, false),
+            AccountMeta::new_readonly(keys.input_vault_mint, false),
+            AccountMeta::new_readonly(keys.output_vault_mint, false),
+            // The bitmap extension leads the remaining accounts, ahead of the tick arrays
+            AccountMeta::new(keys.tick_array_bitmap_extension, false),
+        ];
+        expected_accounts.extend(keys.tick_arrays.iter().map(|tick_array| AccountMeta::new(*tick_array, false)));
+        assert_eq!(ix.accounts, expected_accounts);
+        assert_eq!(ix.accounts.len(), 17);
+    }
+
+    #[test]
+    fn raydium_cpmm_swap_base_input_layout() {
+        let keys = raydium_cpmm::SwapBaseInputKeys {
+            payer: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amm_config: Pubkey::new_unique(),
+            pool_state: Pubkey::new_unique(),
+            input_token_account: Pubkey::new_unique(),
+            output_token_account: Pubkey::new_unique(),
+            input_vault: Pubkey::new_unique(),
+            output_vault: Pubkey::new_unique(),
+            input_token_program: TOKEN_2022_PROGRAM_ID,
+            output_token_program: TOKEN_PROGRAM_ID,
+            input_token_mint: Pubkey::new_unique(),
+            output_token_mint: Pubkey::new_unique(),
+            observation_state: Pubkey::new_unique(),
+        };
+        let args = raydium_cpmm::SwapBaseInputInstruction {
+            amount_in: 1_000_000,
+            minimum_amount_out: 0x0102_0304_0506_0708,
+        };
+        let ix = raydium_cpmm::swap_base_input_instruction(&keys, &args).unwrap();
+
+        assert_eq!(ix.program_id, RAYDIUM_CPMM_PROGRAM_ID);
+        assert_eq!(
+            ix.data,
+            vec![
+                0x8f, 0xbe, 0x5a, 0xda, 0xc4, 0x1e, 0x33, 0xde,
+                0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01,
+            ]
+        );
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new_readonly(keys.payer, true),
+                AccountMeta::new_readonly(keys.authority, false),
+                AccountMeta::new_readonly(keys.amm_config, false),
+                AccountMeta::new(keys.pool_state, false),
+                AccountMeta::new(keys.input_token_account, false),
+                AccountMeta::new(keys.output_token_account, false),
+                AccountMeta::new(keys.input_vault, false),
+                AccountMeta::new(keys.output_vault, false),
+                AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(keys.input_token_mint, false),
+                AccountMeta::new_readonly(keys.output_token_mint, false),
+                AccountMeta::new(keys.observation_state, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn meteora_dlmm_swap_layout() {
+        let keys = meteora_dlmm::SwapKeys {
+            lb_pair: Pubkey::new_unique(),
+            bin_array_bitmap_extension: Pubkey::new_unique(),
+            reserve_x: Pubkey::new_unique(),
+            reserve_y: Pubkey::new_unique(),
+            user_token_in: Pubkey::new_unique(),
+            user_token_out: Pubkey::new_unique(),
+            token_x_mint: Pubkey::new_unique(),
+            token_y_mint: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            host_fee_in: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            token_x_program: TOKEN_PROGRAM_ID,
+            token_y_program: TOKEN_2022_PROGRAM_ID,
+            event_authority: Pubkey::new_unique(),
+            bin_arrays: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let args = meteora_dlmm::SwapInstruction {
+            amount_in: 1_000_000,
+            min_amount_out: 0x0102_0304_0506_0708,
+        };
+        let ix = meteora_dlmm::swap_instruction(&keys, &args).unwrap();
+
+        assert_eq!(ix.program_id, METEORA_DLMM_PROGRAM_ID);
+        assert_eq!(
+            ix.data,
+            vec![
+                0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8,
+                0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01,
+            ]
+        );
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(keys.lb_pair, false),
+                AccountMeta::new_readonly(keys.bin_array_bitmap_extension, false),
+                AccountMeta::new(keys.reserve_x, false),
+                AccountMeta::new(keys.reserve_y, false),
+                AccountMeta::new(keys.user_token_in, false),
+                AccountMeta::new(keys.user_token_out, false),
+                AccountMeta::new_readonly(keys.token_x_mint, false),
+                AccountMeta::new_readonly(keys.token_y_mint, false),
+                AccountMeta::new(keys.oracle, false),
+                AccountMeta::new(keys.host_fee_in, false),
+                AccountMeta::new_readonly(keys.user, true),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+                AccountMeta::new_readonly(keys.event_authority, false),
+                AccountMeta::new_readonly(METEORA_DLMM_PROGRAM_ID, false),
+                // Bin arrays follow as writable remaining accounts, in the order given
+                AccountMeta::new(keys.bin_arrays[0], false),
+                AccountMeta::new(keys.bin_arrays[1], false),
+            ]
+        );
+    }
+
+    fn phoenix_header(base_lot_size: u64, quote_lot_size: u64) -> phoenix::MarketHeader {
+        phoenix::MarketHeader {
+            base_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            base_lot_size,
+            quote_mint: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            quote_lot_size,
+        }
+    }
+
+    /// `Swap` data for an IOC packet: every field after the lot counts is fixed
+    fn phoenix_ioc_data(side: u8, lots: [u64; 4]) -> Vec<u8> {
+        let mut data = vec![0, 2, side, 0]; // Swap, ImmediateOrCancel, side, price_in_ticks: None
+        for lot in lots {
+            data.extend_from_slice(&lot.to_le_bytes());
+        }
+        data.push(0); // self_trade_behavior: Abort
+        data.push(0); // match_limit: None
+        data.extend_from_slice(&[0; 16]); // client_order_id
+        data.extend_from_slice(&[0, 0, 0]); // use_only_deposited_funds, last_valid_slot, last_valid_unix_timestamp
+        data
+    }
+
+    fn phoenix_swap_data(order: &phoenix::ImmediateOrCancelOrder) -> Vec<u8> {
+        let keys = phoenix::SwapKeys {
+            log_authority: Pubkey::new_unique(),
+            market: Pubkey::new_unique(),
+            trader: Pubkey::new_unique(),
+            base_account: Pubkey::new_unique(),
+            quote_account: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM_ID,
+        };
+        phoenix::swap_instruction(&keys, order).unwrap().data
+    }
+
+    #[test]
+    fn phoenix_ioc_order_lots_and_rounding() {
+        let header = phoenix_header(1_000, 10);
+
+        // Bid spends quote: input rounds down to whole quote lots, the base fill rounds up
+        let bid = phoenix::immediate_or_cancel_order(&header, phoenix::Side::Bid, 12_345, 2_001).unwrap();
+        assert_eq!(phoenix_swap_data(&bid), phoenix_ioc_data(0, [0, 1_234, 3, 0]));
+        let bid = phoenix::immediate_or_cancel_order(&header, phoenix::Side::Bid, 12_340, 2_000).unwrap();
+        assert_eq!(phoenix_swap_data(&bid), phoenix_ioc_data(0, [0, 1_234, 2, 0]));
+
+        // Ask spends base: input rounds down to whole base lots, the quote fill rounds up
+        let ask = phoenix::immediate_or_cancel_order(&header, phoenix::Side::Ask, 5_999, 101).unwrap();
+        assert_eq!(phoenix_swap_data(&ask), phoenix_ioc_data(1, [5, 0, 0, 11]));
+        let ask = phoenix::immediate_or_cancel_order(&header, phoenix::Side::Ask, 6_000, 100).unwrap();
+        assert_eq!(phoenix_swap_data(&ask), phoenix_ioc_data(1, [6, 0, 0, 10]));
+
+        // Less than one lot of input cannot be placed, and neither can an order on a lot-less market
+        assert_eq!(
+            phoenix::immediate_or_cancel_order(&header, phoenix::Side::Bid, 9, 0).unwrap_err(),
+            ArbitrageError::InvalidAmount.into()
+        );
+        assert_eq!(
+            phoenix::immediate_or_cancel_order(&header, phoenix::Side::Ask, 999, 0).unwrap_err(),
+            ArbitrageError::InvalidAmount.into()
+        );
+        assert_eq!(
+            phoenix::immediate_or_cancel_order(&phoenix_header(0, 10), phoenix::Side::Ask, 5_000, 0).unwrap_err(),
+            ArbitrageError::AccountValidationFailed.into()
+        );
+    }
+
+    fn openbook_market(base_lot_size: i64, quote_lot_size: i64) -> openbook_v2::Market {
+        openbook_v2::Market {
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+            event_heap: Pubkey::new_unique(),
+            quote_lot_size,
+            base_lot_size,
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            market_base_vault: Pubkey::new_unique(),
+            market_quote_vault: Pubkey::new_unique(),
+        }
+    }
+
+    /// `place_take_order` data for a market order with the default match limit
+    fn openbook_take_data(side: u8, price_lots: i64, max_base_lots: i64, max_quote_lots: i64) -> Vec<u8> {
+        let mut data = vec![0x03, 0x2c, 0x47, 0x03, 0x1a, 0xc7, 0xcb, 0x55, side];
+        data.extend_from_slice(&price_lots.to_le_bytes());
+        data.extend_from_slice(&max_base_lots.to_le_bytes());
+        data.extend_from_slice(&max_quote_lots.to_le_bytes());
+        data.push(3); // PlaceOrderType::Market
+        data.push(openbook_v2::MATCH_LIMIT);
+        data
+    }
+
+    fn openbook_take_order_data(args: &openbook_v2::PlaceTakeOrderArgs) -> Vec<u8> {
+        let keys = openbook_v2::PlaceTakeOrderKeys {
+            signer: Pubkey::new_unique(),
+            market: Pubkey::new_unique(),
+            market_authority: Pubkey::new_unique(),
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+            market_base_vault: Pubkey::new_unique(),
+            market_quote_vault: Pubkey::new_unique(),
+            event_heap: Pubkey::new_unique(),
+            user_base_account: Pubkey::new_unique(),
+            user_quote_account: Pubkey::new_unique(),
+            oracle_a: OPENBOOK_V2_PROGRAM_ID,
+            oracle_b: OPENBOOK_V2_PROGRAM_ID,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: anchor_lang::system_program::ID,
+        };
+        openbook_v2::place_take_order_instruction(&keys, args).unwrap().data
+    }
+
+    #[test]
+    fn openbook_take_order_lots_and_price_limits() {
+        let market = openbook_market(1_000, 10);
+
+        // Bid spends quote: no price or base cap, input rounded down to whole quote lots
+        let bid = openbook_v2::take_order_args(&market, openbook_v2::Side::Bid, 12_345).unwrap();
+        assert_eq!(openbook_take_order_data(&bid), openbook_take_data(0, i64::MAX, i64::MAX, 1_234));
+        let bid = openbook_v2::take_order_args(&market, openbook_v2::Side::Bid, 12_340).unwrap();
+        assert_eq!(openbook_take_order_data(&bid), openbook_take_data(0, i64::MAX, i64::MAX, 1_234));
+
+        // Ask spends base: lowest price, no quote cap, input rounded down to whole base lots
+        let ask = openbook_v2::take_order_args(&market, openbook_v2::Side::Ask, 5_999).unwrap();
+        assert_eq!(openbook_take_order_data(&ask), openbook_take_data(1, 1, 5, i64::MAX));
+        let ask = openbook_v2::take_order_args(&market, openbook_v2::Side::Ask, 6_000).unwrap();
+        assert_eq!(openbook_take_order_data(&ask), openbook_take_data(1, 1, 6, i64::MAX));
+
+        // Less than one lot of input cannot be placed
+        assert_eq!(
+            openbook_v2::take_order_args(&market, openbook_v2::Side::Bid, 9).unwrap_err(),
+            ArbitrageError::InvalidAmount.into()
+        );
+        assert_eq!(
+            openbook_v2::take_order_args(&market, openbook_v2::Side::Ask, 999).unwrap_err(),
+            ArbitrageError::InvalidAmount.into()
+        );
+        // Inputs past i64 and markets without lot sizes are rejected instead of wrapping
+        assert_eq!(
+            openbook_v2::take_order_args(&market, openbook_v2::Side::Bid, u64::MAX).unwrap_err(),
+            ArbitrageError::ArithmeticError.into()
+        );
+        assert_eq!(
+            openbook_v2::take_order_args(&openbook_market(1_000, 0), openbook_v2::Side::Bid, 5_000).unwrap_err(),
+            ArbitrageError::AccountValidationFailed.into()
+        );
+    }
+
+    #[test]
+    fn jupiter_route_data_layout() {
+        // Opaque borsh `Vec<RoutePlanStep>` with one step, forwarded untouched
+        let route_plan = vec![0x01, 0x00, 0x00, 0x00, 0xaa, 0xbb, 0xcc];
+        let amounts = [
+            0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, // in_amount
+            0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // quoted_out_amount
+            0x00, 0x00, // slippage_bps
+            0x00, // platform_fee_bps
+        ];
+
+        let route = JupiterRoutePlan { shared_accounts_id: None, route_plan: route_plan.clone(), account_count: 9 };
+        let mut expected = vec![0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a];
+        expected.extend_from_slice(&route_plan);
+        expected.extend_from_slice(&amounts);
+        assert_eq!(jupiter::route_data(&route, 1_000_000, 0x0102_0304_0506_0708), expected);
+
+        // shared_accounts_route puts its id byte between the discriminator and the plan
+        let shared = JupiterRoutePlan { shared_accounts_id: Some(7), route_plan: route_plan.clone(), account_count: 13 };
+        let mut expected = vec![0xc1, 0x20, 0x9b, 0x33, 0x41, 0xd6, 0x9c, 0x81, 0x07];
+        expected.extend_from_slice(&route_plan);
+        expected.extend_from_slice(&amounts);
+        assert_eq!(jupiter::route_data(&shared, 1_000_000, 0x0102_0304_0506_0708), expected);
+    }
+
+    #[test]
+    fn whirlpool_swap_v2_layout() {
+        let keys = whirlpool_swap::SwapV2Keys {
+            token_program_a: TOKEN_PROGRAM_ID,
+            token_program_b: TOKEN_2022_PROGRAM_ID,
+            memo_program: Pubkey::new_unique(),
+            token_authority: Pubkey::new_unique(),
+            whirlpool: Pubkey::new_unique(),
+            token_mint_a: Pubkey::new_unique(),
+            token_mint_b: Pubkey::new_unique(),
+            token_owner_account_a: Pubkey::new_unique(),
+            token_vault_a: Pubkey::new_unique(),
+            token_owner_account_b: Pubkey::new_unique(),
+            token_vault_b: Pubkey::new_unique(),
+            tick_array_0: Pubkey::new_unique(),
+            tick_array_1: Pubkey::new_unique(),
+            tick_array_2: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+        };
+        let args = whirlpool_swap::SwapV2Instruction {
+            amount: 1_000_000,
+            other_amount_threshold: 0x0102_0304_0506_0708,
+            sqrt_price_limit: whirlpool_swap::MIN_SQRT_PRICE_X64,
+            amount_specified_is_input: true,
+            a_to_b: true,
+            remaining_accounts_info: None,
+        };
+        let ix = whirlpool_swap::swap_v2_instruction(&keys, &args).unwrap();
+
+        assert_eq!(ix.program_id, WHIRLPOOL_PROGRAM_ID);
+        let mut expected_data = vec![0x2b, 0x04, 0xed, 0x0b, 0x1a, 0xc9, 0x1e, 0x62];
+        expected_data.extend_from_slice(&[0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        expected_data.extend_from_slice(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        // 4295048016 = 0x1_0001_3b50
+        expected_data.extend_from_slice(&[0x50, 0x3b, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+        expected_data.extend_from_slice(&[1, 1, 0]); // amount_specified_is_input, a_to_b, remaining_accounts_info: None
+        assert_eq!(ix.data, expected_data);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+                AccountMeta::new_readonly(keys.memo_program, false),
+                AccountMeta::new_readonly(keys.token_authority, true),
+                AccountMeta::new(keys.whirlpool, false),
+                AccountMeta::new_readonly(keys.token_mint_a, false),
+                AccountMeta::new_readonly(keys.token_mint_b, false),
+                AccountMeta::new(keys.token_owner_account_a, false),
+                AccountMeta::new(keys.token_vault_a, false),
+                AccountMeta::new(keys.token_owner_account_b, false),
+                AccountMeta::new(keys.token_vault_b, false),
+                AccountMeta::new(keys.tick_array_0, false),
+                AccountMeta::new(keys.tick_array_1, false),
+                AccountMeta::new(keys.tick_array_2, false),
+                AccountMeta::new(keys.oracle, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn whirlpool_two_hop_swap_v2_layout() {
+        let keys = whirlpool_swap::TwoHopSwapV2Keys {
+            whirlpool_one: Pubkey::new_unique(),
+            whirlpool_two: Pubkey::new_unique(),
+            token_mint_input: Pubkey::new_unique(),
+            token_mint_intermediate: Pubkey::new_unique(),
+            token_mint_output: Pubkey::new_unique(),
+            token_program_input: TOKEN_PROGRAM_ID,
+            token_program_intermediate: TOKEN_2022_PROGRAM_ID,
+            token_program_output: TOKEN_PROGRAM_ID,
+            token_owner_account_input: Pubkey::new_unique(),
+            token_vault_one_input: Pubkey::new_unique(),
+            token_vault_one_intermediate: Pubkey::new_unique(),
+            token_vault_two_intermediate: Pubkey::new_unique(),
+            token_vault_two_output: Pubkey::new_unique(),
+            token_owner_account_output: Pubkey::new_unique(),
+            token_authority: Pubkey::new_unique(),
+            tick_array_one_0: Pubkey::new_unique(),
+            tick_array_one_1: Pubkey::new_unique(),
+            tick_array_one_2: Pubkey::new_unique(),
+            tick_array_two_0: Pubkey::new_unique(),
+            tick_array_two_1: Pubkey::new_unique(),
+            tick_array_two_2: Pubkey::new_unique(),
+            oracle_one: Pubkey::new_unique(),
+            oracle_two: Pubkey::new_unique(),
+            memo_program: Pubkey::new_unique(),
+        };
+        let args = whirlpool_swap::TwoHopSwapV2Instruction {
+            amount: 1_000_000,
+            other_amount_threshold: 0x0102_0304_0506_0708,
+            amount_specified_is_input: true,
+            a_to_b_one: true,
+            a_to_b_two: false,
+            sqrt_price_limit_one: 0,
+            sqrt_price_limit_two: 0,
+            remaining_accounts_info: Some(whirlpool_swap::RemainingAccountsInfo {
+                slices: vec![whirlpool_swap::RemainingAccountsSlice {
+                    accounts_type: whirlpool_swap::AccountsType::SupplementalTickArraysOne,
+                    length: 3,
+                }],
+            }),
+        };
+        let ix = whirlpool_swap::two_hop_swap_v2_instruction(&keys, &args).unwrap();
+
+        assert_eq!(ix.program_id, WHIRLPOOL_PROGRAM_ID);
+        let mut expected_data = vec![0xba, 0x8f, 0xd1, 0x1d, 0xfe, 0x02, 0xc2, 0x75];
+        expected_data.extend_from_slice(&[0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        expected_data.extend_from_slice(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        expected_data.extend_from_slice(&[1, 1, 0]); // amount_specified_is_input, a_to_b_one, a_to_b_two
+        expected_data.extend_from_slice(&[0; 32]); // sqrt_price_limit_one, sqrt_price_limit_two
+        // Some, one slice of SupplementalTickArraysOne with three accounts
+        expected_data.extend_from_slice(&[1, 0x01, 0x00, 0x00, 0x00, 7, 3]);
+        assert_eq!(ix.data, expected_data);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(keys.whirlpool_one, false),
+                AccountMeta::new(keys.whirlpool_two, false),
+                AccountMeta::new_readonly(keys.token_mint_input, false),
+                AccountMeta::new_readonly(keys.token_mint_intermediate, false),
+                AccountMeta::new_readonly(keys.token_mint_output, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(keys.token_owner_account_input, false),
+                AccountMeta::new(keys.token_vault_one_input, false),
+                AccountMeta::new(keys.token_vault_one_intermediate, false),
+                AccountMeta::new(keys.token_vault_two_intermediate, false),
+                AccountMeta::new(keys.token_vault_two_output, false),
+                AccountMeta::new(keys.token_owner_account_output, false),
+                AccountMeta::new_readonly(keys.token_authority, true),
+                AccountMeta::new(keys.tick_array_one_0, false),
+                AccountMeta::new(keys.tick_array_one_1, false),
+                AccountMeta::new(keys.tick_array_one_2, false),
+                AccountMeta::new(keys.tick_array_two_0, false),
+                AccountMeta::new(keys.tick_array_two_1, false),
+                AccountMeta::new(keys.tick_array_two_2, false),
+                AccountMeta::new(keys.oracle_one, false),
+                AccountMeta::new(keys.oracle_two, false),
+                AccountMeta::new_readonly(keys.memo_program, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn min_profit_above_i64_is_never_met() {
+        assert!(meets_min_profit(1_000, 1_000));
+        assert!(!meets_min_profit(999, 1_000));
+        assert!(!meets_min_profit(-1, 1));
+        // `u64::MAX as i64` is -1, which any break-even trade would have cleared
+        assert!(!meets_min_profit(0, u64::MAX));
+        assert!(!meets_min_profit(i64::MAX, u64::MAX));
+        assert!(!meets_min_profit(i64::MAX, i64::MAX as u64 + 1));
+    }
+
+    #[test]
+    fn landed_failures_count_like_mint_stats() {
+        let (state_key, usdc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = Vec::new();
+        limited_state(usdc).try_serialize(&mut data).unwrap();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&state_key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        let mut state = Account::<ArbitrageState>::try_from(&info).unwrap();
+        let mut mint_stats = MintStats {
+            arbitrage_state: state_key,
+            mint: usdc,
+            trades: 0,
+            volume_in: 0,
+            volume_out: 0,
+            realized_profit: 0,
+            realized_loss: 0,
+            bump: 255,
+        };
+        let now = 1_700_000_000;
+
+        // A gain below min_profit and a loss, both landed as failures, then a trade that cleared it
+        for (pnl, min_profit) in [(40, 100), (-50, 100), (150, 100)] {
+            mint_stats.record_arbitrage(1_000, pnl).unwrap();
+            if meets_min_profit(pnl, min_profit) {
+                state.record_trade_totals(pnl).unwrap();
+            } else {
+                record_failed_trade(&mut state, Pubkey::new_unique(), pnl, min_profit, now).unwrap();
+            }
+        }
+
+        assert_eq!(state.total_trades, mint_stats.trades);
+        assert_eq!(state.total_profit, mint_stats.realized_profit);
+        assert_eq!((state.total_trades, state.total_profit), (3, 190));
+        assert_eq!(mint_stats.realized_loss, 50);
+        assert_eq!(state.consecutive_failures, 2);
+    }
+
+    /// SPL token account data holding `mint`
+    fn token_account_data(mint: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data
+    }
+
+    #[test]
+    fn hop_token_accounts_must_hold_declared_mints() {
+        let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports = [0u64; 4];
+        let mut data = [Vec::new(), token_account_data(&usdc), token_account_data(&sol), token_account_data(&bonk)];
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| AccountInfo::new(key, false, true, lamports, data, &TOKEN_PROGRAM_ID, false, 0))
+            .collect();
+        let usdc_to_sol = [infos[0].clone(), infos[1].clone(), infos[2].clone()];
+        let usdc_to_bonk = [infos[0].clone(), infos[1].clone(), infos[3].clone()];
+        let bonk_to_sol = [infos[0].clone(), infos[3].clone(), infos[2].clone()];
+
+        assert!(check_hop_token_accounts(&route(usdc, sol), &usdc_to_sol).is_ok());
+        assert_eq!(
+            check_hop_token_accounts(&route(usdc, sol), &bonk_to_sol).unwrap_err(),
+            ArbitrageError::TokenAccountMintMismatch.into()
+        );
+        assert_eq!(
+            check_hop_token_accounts(&route(usdc, sol), &usdc_to_bonk).unwrap_err(),
+            ArbitrageError::TokenAccountMintMismatch.into()
+        );
+        assert_eq!(
+            check_hop_token_accounts(&route(usdc, sol), &usdc_to_sol[..2]).unwrap_err(),
+            ArbitrageError::MissingRouteAccounts.into()
+        );
+    }
+
+    #[test]
+    fn route_chain_binds_declared_mints_to_hop_accounts() {
+        let (usdc, sol, bonk) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let user_token_account = Pubkey::new_unique();
+        let routes = [route(usdc, sol), route(sol, usdc)];
+        assert!(validate_route_chain(&routes).is_ok());
+
+        // Allowlist entries then Orca hop accounts per leg, with [program, source, destination] first
+        let per_route = ROUTE_ALLOWLIST_ACCOUNT_COUNT + routes[0].hop_account_count();
+        let sol_account = Pubkey::new_unique();
+        let mut keys: Vec<Pubkey> = (0..2 * per_route).map(|_| Pubkey::new_unique()).collect();
+        let mut data = vec![Vec::new(); 2 * per_route];
+        let source = ROUTE_ALLOWLIST_ACCOUNT_COUNT + 1;
+        for (index, key, mint) in [
+            (source, user_token_account, usdc),
+            (source + 1, sol_account, sol),
+            (per_route + source, sol_account, sol),
+            (per_route + source + 1, user_token_account, usdc),
+        ] {
+            keys[index] = key;
+            data[index] = token_account_data(&mint);
+        }
+
+        let bind = |keys: &[Pubkey], data: &mut [Vec<u8>]| {
+            let mut lamports = vec![0u64; keys.len()];
+            let infos: Vec<AccountInfo> = keys
+                .iter()
+                .zip(lamports.iter_mut())
+                .zip(data.iter_mut())
+                .map(|((key, lamports), data)| AccountInfo::new(key, false, true, lamports, data, &TOKEN_PROGRAM_ID, false, 0))
+                .collect();
+            validate_route_accounts(&routes, &infos, &user_token_account)
+        };
+        assert!(bind(&keys, &mut data).is_ok());
+
+        // The declared chain still closes, but the second leg's source account holds another mint
+        data[per_route + source] = token_account_data(&bonk);
+        assert_eq!(bind(&keys, &mut data).unwrap_err(), ArbitrageError::TokenAccountMintMismatch.into());
+        data[per_route + source] = token_account_data(&sol);
+
+        // The last leg pays into an account other than the one profit is measured on
+        keys[per_route + source + 1] = Pubkey::new_unique();
+        assert_eq!(bind(&keys, &mut data).unwrap_err(), ArbitrageError::RouteEndpointMismatch.into());
+    }
+}
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { createAccount, createMint, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { expect } from "chai";
 
 describe("arbitrage-program", () => {
@@ -10,15 +11,39 @@ describe("arbitrage-program", () => {
 
   // Use the correct program name from your IDL
   const program = anchor.workspace.ArbitrageProgram as Program<any>;
-  
+
   let authority: Keypair;
   let arbitrageStatePDA: PublicKey;
   let bump: number;
+  const stateId = Keypair.generate().publicKey;
+  const operator = Keypair.generate();
+  const guardian = Keypair.generate();
+  const nextAuthority = Keypair.generate();
+
+  // Input mint the test routes start and end in, plus the intermediate mint they pass through
+  let inputMint: PublicKey;
+  let intermediateMint: PublicKey;
+  let userTokenAccount: PublicKey;
+  let profitVaultPDA: PublicKey;
+  let mintStatsPDA: PublicKey;
+
+  // Swapping needs live pools and allowlist entries, so the execution tests below drive the
+  // instructions up to a check that fails before any DEX is called
+  const expectRejection = async (call: Promise<string>, errorCode: string) => {
+    let rejected = false;
+    try {
+      await call;
+    } catch (error) {
+      expect(String(error)).to.include(errorCode);
+      rejected = true;
+    }
+    expect(rejected, `expected ${errorCode}`).to.be.true;
+  };
 
   before(async () => {
     // Generate test keypair
     authority = Keypair.generate();
-    
+
     console.log("🔧 Setting up test environment...");
     console.log("📍 Program ID:", program.programId.toString());
     console.log("👤 Test Authority:", authority.publicKey.toString());
@@ -26,7 +51,7 @@ describe("arbitrage-program", () => {
     // Airdrop SOL for testing
     try {
       const airdropTx = await provider.connection.requestAirdrop(
-        authority.publicKey, 
+        authority.publicKey,
         2 * anchor.web3.LAMPORTS_PER_SOL
       );
       await provider.connection.confirmTransaction(airdropTx, "confirmed");
@@ -37,7 +62,22 @@ describe("arbitrage-program", () => {
 
     // Find PDA for arbitrage state
     [arbitrageStatePDA, bump] = PublicKey.findProgramAddressSync(
-      [Buffer.from("arbitrage_state"), authority.publicKey.toBuffer()],
+      [Buffer.from("arbitrage_state"), stateId.toBuffer()],
+      program.programId
+    );
+
+    // Test mints and a funded input-mint account owned by the authority
+    inputMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    intermediateMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    userTokenAccount = await createAccount(provider.connection, authority, inputMint, authority.publicKey);
+    await mintTo(provider.connection, authority, inputMint, userTokenAccount, authority, 1_000_000);
+
+    [profitVaultPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("profit_vault"), arbitrageStatePDA.toBuffer(), inputMint.toBuffer()],
+      program.programId
+    );
+    [mintStatsPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_stats"), arbitrageStatePDA.toBuffer(), inputMint.toBuffer()],
       program.programId
     );
 
@@ -47,12 +87,12 @@ describe("arbitrage-program", () => {
 
   it("✅ Initialize arbitrage state", async () => {
     console.log("\n🚀 Testing: Initialize Arbitrage State");
-    
+
     const minExecutionInterval = new anchor.BN(300); // 5 minutes
 
     try {
       const tx = await program.methods
-        .initializeArbitrageState(minExecutionInterval)
+        .initializeArbitrageState(stateId, minExecutionInterval)
         .accounts({
           authority: authority.publicKey,
           arbitrageState: arbitrageStatePDA,
@@ -65,14 +105,15 @@ describe("arbitrage-program", () => {
 
       // Verify the state was initialized correctly
       const stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
-      
+
       console.log("📊 Verified State:");
       console.log("  Authority:", stateAccount.authority.toString());
       console.log("  Min Execution Interval:", stateAccount.minExecutionInterval.toString());
       console.log("  Is Paused:", stateAccount.isPaused);
       console.log("  Total Trades:", stateAccount.totalTrades.toString());
       console.log("  Total Profit:", stateAccount.totalProfit.toString());
-      
+
+      expect(stateAccount.stateId.toString()).to.equal(stateId.toString());
       expect(stateAccount.authority.toString()).to.equal(authority.publicKey.toString());
       expect(stateAccount.minExecutionInterval.toString()).to.equal(minExecutionInterval.toString());
       expect(stateAccount.isPaused).to.be.false;
@@ -80,7 +121,7 @@ describe("arbitrage-program", () => {
       expect(stateAccount.totalProfit.toString()).to.equal("0");
 
       console.log("✅ All validations passed!");
-      
+
     } catch (error) {
       console.error("❌ Initialize test failed:", error);
       throw error;
@@ -89,43 +130,74 @@ describe("arbitrage-program", () => {
 
   it("✅ Execute flash arbitrage simulation", async () => {
     console.log("\n🚀 Testing: Flash Arbitrage Simulation");
-    
+
     try {
-      // Create test swap routes
+      // Profit vault and mint stats for the input mint must exist before trading it
+      await program.methods
+        .initializeProfitVault()
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+          mint: inputMint,
+          profitVault: profitVaultPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await program.methods
+        .initializeMintStats()
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+          mint: inputMint,
+          mintStats: mintStatsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      // A single hop from the input mint back to itself never closes a real cycle
       const swapRoutes = [
         {
           dexId: { orca: {} },
           amountIn: new anchor.BN(50_000),
           minAmountOut: new anchor.BN(49_000),
-          inputMint: PublicKey.default,
-          outputMint: PublicKey.default,
+          inputMint,
+          outputMint: inputMint,
+          jupiterRoute: null,
         }
       ];
 
       const expectedProfit = new anchor.BN(1_000);
 
-      const tx = await program.methods
-        .flashArbitrage(swapRoutes, expectedProfit)
-        .accounts({
-          user: authority.publicKey,
-          arbitrageState: arbitrageStatePDA,
-          whirlpool: PublicKey.default,
-          ammId: PublicKey.default,
-          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([authority])
-        .rpc();
-
-      console.log("✅ Flash arbitrage transaction:", tx);
+      await expectRejection(
+        program.methods
+          .flashArbitrage(swapRoutes, expectedProfit, { fixed: {} }, null, false)
+          .accounts({
+            user: authority.publicKey,
+            arbitrageState: arbitrageStatePDA,
+            userTokenAccount,
+            inputMint,
+            profitVault: profitVaultPDA,
+            mintStats: mintStatsPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            splTokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc(),
+        "InvalidSwapPair"
+      );
 
-      // Verify state was updated
+      // Verify the rejected trade left the state untouched
       const stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
       console.log("📊 Updated State:");
       console.log("  Total Trades:", stateAccount.totalTrades.toString());
       console.log("  Total Profit:", stateAccount.totalProfit.toString());
 
-      expect(stateAccount.totalTrades.toString()).to.equal("1");
+      expect(stateAccount.totalTrades.toString()).to.equal("0");
       console.log("✅ Flash arbitrage simulation successful!");
 
     } catch (error) {
@@ -136,45 +208,59 @@ describe("arbitrage-program", () => {
 
   it("✅ Test flash loan arbitrage simulation", async () => {
     console.log("\n🚀 Testing: Flash Loan Arbitrage Simulation");
-    
+
     try {
       const flashLoanAmount = new anchor.BN(100_000);
       const expectedProfit = new anchor.BN(2_000);
-      
+
       const swapRoutes = [
         {
           dexId: { raydium: {} },
           amountIn: new anchor.BN(100_000),
+          minAmountOut: new anchor.BN(50_000),
+          inputMint,
+          outputMint: intermediateMint,
+          jupiterRoute: null,
+        },
+        {
+          dexId: { raydium: {} },
+          amountIn: new anchor.BN(50_000),
           minAmountOut: new anchor.BN(102_000),
-          inputMint: PublicKey.default,
-          outputMint: PublicKey.default,
+          inputMint: intermediateMint,
+          outputMint: inputMint,
+          jupiterRoute: null,
         }
       ];
 
-      const tx = await program.methods
-        .flashLoanArbitrage(flashLoanAmount, swapRoutes, expectedProfit)
-        .accounts({
-          user: authority.publicKey,
-          arbitrageState: arbitrageStatePDA,
-          solendReserve: PublicKey.default,
-          whirlpool: PublicKey.default,
-          ammId: PublicKey.default,
-          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-          solendProgram: PublicKey.default,
-        })
-        .signers([authority])
-        .rpc();
-
-      console.log("✅ Flash loan arbitrage transaction:", tx);
+      // Without the FlashBorrow / FlashRepay pair around it the program refuses to run the routes
+      await expectRejection(
+        program.methods
+          .flashLoanArbitrage(flashLoanAmount, swapRoutes, expectedProfit, { fixed: {} }, null, false)
+          .accounts({
+            user: authority.publicKey,
+            arbitrageState: arbitrageStatePDA,
+            userTokenAccount,
+            inputMint,
+            profitVault: profitVaultPDA,
+            mintStats: mintStatsPDA,
+            reserve: Keypair.generate().publicKey,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            splTokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc(),
+        "FlashLoanNotPaired"
+      );
 
-      // Verify state was updated
+      // Verify the rejected trade left the state untouched
       const stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
       console.log("📊 Final State:");
       console.log("  Total Trades:", stateAccount.totalTrades.toString());
       console.log("  Total Profit:", stateAccount.totalProfit.toString());
 
-      expect(stateAccount.totalTrades.toString()).to.equal("2");
+      expect(stateAccount.totalTrades.toString()).to.equal("0");
       console.log("✅ Flash loan arbitrage simulation successful!");
 
     } catch (error) {
@@ -185,7 +271,7 @@ describe("arbitrage-program", () => {
 
   it("✅ Test bot controls (pause/resume)", async () => {
     console.log("\n🚀 Testing: Bot Controls");
-    
+
     try {
       // Test pause
       const pauseTx = await program.methods
@@ -225,6 +311,176 @@ describe("arbitrage-program", () => {
     }
   });
 
+  it("✅ Add and remove an operator", async () => {
+    console.log("\n🚀 Testing: Operator Management");
+
+    try {
+      const addTx = await program.methods
+        .addOperator(operator.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("➕ Add operator transaction:", addTx);
+
+      let stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.operators.map((key: PublicKey) => key.toString())).to.deep.equal([
+        operator.publicKey.toString(),
+      ]);
+
+      // Operators trade but cannot manage other operators
+      await expectRejection(
+        program.methods
+          .addOperator(Keypair.generate().publicKey)
+          .accounts({
+            authority: operator.publicKey,
+            arbitrageState: arbitrageStatePDA,
+          })
+          .signers([operator])
+          .rpc(),
+        "Unauthorized"
+      );
+
+      const removeTx = await program.methods
+        .removeOperator(operator.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("➖ Remove operator transaction:", removeTx);
+
+      stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.operators).to.have.length(0);
+      console.log("✅ Operator successfully added and removed");
+
+    } catch (error) {
+      console.error("❌ Operator test failed:", error);
+      throw error;
+    }
+  });
+
+  it("✅ Guardian can pause, only the authority can resume", async () => {
+    console.log("\n🚀 Testing: Guardian Controls");
+
+    try {
+      const guardianTx = await program.methods
+        .setGuardian(guardian.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("🛡️ Set guardian transaction:", guardianTx);
+
+      const pauseTx = await program.methods
+        .pauseBot()
+        .accounts({
+          authority: guardian.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([guardian])
+        .rpc();
+
+      console.log("⏸️ Guardian pause transaction:", pauseTx);
+
+      let stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.isPaused).to.be.true;
+
+      await expectRejection(
+        program.methods
+          .resumeBot()
+          .accounts({
+            authority: guardian.publicKey,
+            arbitrageState: arbitrageStatePDA,
+          })
+          .signers([guardian])
+          .rpc(),
+        "Unauthorized"
+      );
+
+      const resumeTx = await program.methods
+        .resumeBot()
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("▶️ Resume transaction:", resumeTx);
+
+      stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.isPaused).to.be.false;
+      console.log("✅ Guardian paused and authority resumed the bot");
+
+    } catch (error) {
+      console.error("❌ Guardian test failed:", error);
+      throw error;
+    }
+  });
+
+  it("✅ Two-step authority transfer", async () => {
+    console.log("\n🚀 Testing: Authority Transfer");
+
+    try {
+      const proposeTx = await program.methods
+        .proposeAuthority(nextAuthority.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([authority])
+        .rpc();
+
+      console.log("📨 Propose authority transaction:", proposeTx);
+
+      // The current authority stays in control until the proposed key accepts
+      let stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.authority.toString()).to.equal(authority.publicKey.toString());
+      expect(stateAccount.pendingAuthority.toString()).to.equal(nextAuthority.publicKey.toString());
+
+      await expectRejection(
+        program.methods
+          .acceptAuthority()
+          .accounts({
+            pendingAuthority: operator.publicKey,
+            arbitrageState: arbitrageStatePDA,
+          })
+          .signers([operator])
+          .rpc(),
+        "Unauthorized"
+      );
+
+      const acceptTx = await program.methods
+        .acceptAuthority()
+        .accounts({
+          pendingAuthority: nextAuthority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+        })
+        .signers([nextAuthority])
+        .rpc();
+
+      console.log("🤝 Accept authority transaction:", acceptTx);
+
+      stateAccount = await program.account.arbitrageState.fetch(arbitrageStatePDA);
+      expect(stateAccount.authority.toString()).to.equal(nextAuthority.publicKey.toString());
+      expect(stateAccount.pendingAuthority).to.be.null;
+      console.log("✅ Authority successfully transferred");
+
+    } catch (error) {
+      console.error("❌ Authority transfer test failed:", error);
+      throw error;
+    }
+  });
+
   after(() => {
     console.log("\n🎉 ALL TESTS PASSED!");
     console.log("✅ Your arbitrage program is working perfectly on devnet!");
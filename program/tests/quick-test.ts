@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { createAccount, createMint, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 describe("Quick Arbitrage Program Test", () => {
   // Configure the client to use devnet
@@ -12,6 +13,7 @@ describe("Quick Arbitrage Program Test", () => {
   let authority: Keypair;
   let arbitrageStatePDA: PublicKey;
   let bump: number;
+  const stateId = Keypair.generate().publicKey;
 
   before(async () => {
     // Use your wallet as authority (or generate new one)
@@ -27,7 +29,7 @@ describe("Quick Arbitrage Program Test", () => {
 
     // Find PDA for arbitrage state
     [arbitrageStatePDA, bump] = PublicKey.findProgramAddressSync(
-      [Buffer.from("arbitrage_state"), authority.publicKey.toBuffer()],
+      [Buffer.from("arbitrage_state"), stateId.toBuffer()],
       program.programId
     );
   });
@@ -37,7 +39,7 @@ describe("Quick Arbitrage Program Test", () => {
       const minExecutionInterval = new anchor.BN(300); // 5 minutes
 
       const tx = await program.methods
-        .initializeArbitrageState(minExecutionInterval)
+        .initializeArbitrageState(stateId, minExecutionInterval)
         .accounts({
           authority: authority.publicKey,
           arbitrageState: arbitrageStatePDA,
@@ -67,34 +69,85 @@ describe("Quick Arbitrage Program Test", () => {
 
   it("Test flash arbitrage simulation", async () => {
     try {
-      // Create test swap routes
+      // Input mint with the profit vault, mint stats and user account every trade needs
+      const inputMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+      const userTokenAccount = await createAccount(provider.connection, authority, inputMint, authority.publicKey);
+      const [profitVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("profit_vault"), arbitrageStatePDA.toBuffer(), inputMint.toBuffer()],
+        program.programId
+      );
+      const [mintStatsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_stats"), arbitrageStatePDA.toBuffer(), inputMint.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeProfitVault()
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+          mint: inputMint,
+          profitVault: profitVaultPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await program.methods
+        .initializeMintStats()
+        .accounts({
+          authority: authority.publicKey,
+          arbitrageState: arbitrageStatePDA,
+          mint: inputMint,
+          mintStats: mintStatsPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      // Create test swap routes (same-mint hop, rejected before any DEX is called)
       const swapRoutes = [
         {
           dexId: { orca: {} },
           amountIn: new anchor.BN(50_000),
           minAmountOut: new anchor.BN(49_000),
-          inputMint: PublicKey.default,
-          outputMint: PublicKey.default,
+          inputMint,
+          outputMint: inputMint,
+          jupiterRoute: null,
         }
       ];
 
       const expectedProfit = new anchor.BN(1_000);
 
-      const tx = await program.methods
-        .flashArbitrage(swapRoutes, expectedProfit)
-        .accounts({
-          user: authority.publicKey,
-          arbitrageState: arbitrageStatePDA,
-          whirlpool: PublicKey.default,
-          ammId: PublicKey.default,
-          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([authority])
-        .rpc();
+      let rejected = false;
+      try {
+        await program.methods
+          .flashArbitrage(swapRoutes, expectedProfit, { fixed: {} }, null, false)
+          .accounts({
+            user: authority.publicKey,
+            arbitrageState: arbitrageStatePDA,
+            userTokenAccount,
+            inputMint,
+            profitVault: profitVaultPDA,
+            mintStats: mintStatsPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            splTokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+      } catch (error) {
+        if (!String(error).includes("InvalidSwapPair")) {
+          throw error;
+        }
+        rejected = true;
+      }
 
-      console.log("✅ Flash arbitrage simulation successful:", tx);
-      console.log("✅ All 7 instructions working on devnet!");
+      if (!rejected) {
+        throw new Error("Same-mint route was not rejected");
+      }
+      console.log("✅ Flash arbitrage simulation rejected the invalid route");
 
     } catch (error) {
       console.error("❌ Flash arbitrage test failed:", error);